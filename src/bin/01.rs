@@ -1,3 +1,5 @@
+use advent_of_code::helpers::{parse::paragraphs, top_k::TopK};
+
 /*
 --- Day 1: Calorie Counting ---
 Santa's reindeer typically eat regular reindeer food, but they need a lot of magical energy to deliver presents on Christmas. For that, their favorite snack is a special type of star fruit that only grows deep in the jungle. The Elves have brought you on their annual expedition to the grove where the fruit grows.
@@ -41,21 +43,15 @@ In case the Elves get hungry and need extra snacks, they need to know which Elf
 
 Find the Elf carrying the most Calories. *How many total Calories is that Elf carrying?*
 */
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut sum: u32 = 0;
-    let mut max: u32 = 0;
-
-    for s in input.split("\n") {
-        if s.is_empty() {
-            max = max.max(sum);
-            sum = 0;
-        } else {
-            let num: u32 = s.parse().unwrap();
-            sum += num;
-        }
-    }
+fn elf_totals(input: &str) -> Vec<u32> {
+    paragraphs(input)
+        .iter()
+        .map(|elf| elf.lines().map(|l| l.parse::<u32>().unwrap()).sum())
+        .collect()
+}
 
-    Some(max)
+pub fn part_one(input: &str) -> Option<u32> {
+    elf_totals(input).into_iter().max()
 }
 
 /*
@@ -69,53 +65,8 @@ In the example above, the top three Elves are the fourth Elf (with 24000 Calorie
 Find the top three Elves carrying the most Calories. How many Calories are those Elves carrying in total?
 */
 
-struct TopThree {
-    vec: Vec<u32>
-}
-
-impl TopThree {
-    fn new () -> Self {
-        Self {
-            vec: Vec::from([0, 0, 0])
-        }
-    }
-
-    fn sum(&self) -> u32 {
-        println!("{:?}", self.vec);
-        self.vec.iter().sum()
-    }
-
-    fn add(&mut self, item: u32) {
-        // remove the last element
-        let last_el = self.vec.pop().unwrap();
-        let new_item = last_el.max(item);
-
-        if new_item > self.vec[0] {
-            self.vec.insert(0, new_item);
-        } else if new_item > self.vec[1] {
-            self.vec.insert(1, new_item);
-        } else {
-            self.vec.push(new_item);
-        }
-    }
-}
-
-
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut t = TopThree::new();
-    let mut sum = 0;
-
-    for s in input.split("\n") {
-        if s.is_empty() {
-            t.add(sum);
-            sum = 0;
-        } else {
-            let num: u32 = s.parse().unwrap();
-            sum += num;
-        }
-    }
-
-    Some(t.sum())
+    Some(TopK::of(3, elf_totals(input)).sum())
 }
 
 fn main() {
@@ -131,12 +82,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 1);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), Some(24000));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 1);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(45000));
     }
 }
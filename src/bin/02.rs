@@ -1,4 +1,4 @@
-/**
+/*
 --- Day 2: Rock Paper Scissors ---
 The Elves begin to set up camp on the beach. To decide whose tent gets to be closest to the snack storage, a giant Rock Paper Scissors tournament is already in progress.
 
@@ -27,7 +27,7 @@ In this example, if you were to follow the strategy guide, you would get a total
 What would your total score be if everything goes exactly according to your strategy guide?
 */
 
-/**
+/*
  * rules:
 { A => rock, B => paper, C => Scissors }
 { X => rock, Y => paper, Z => Scissors }
@@ -48,18 +48,6 @@ enum Result {
     Equal,
 }
 
-impl Result {
-    fn reversed(&self) -> Result {
-        if self == &Result::Win {
-            Result::Lose
-        } else if self == &Result::Lose {
-            Result::Win
-        } else {
-            Result::Equal
-        }
-    }
-}
-
 struct Rule {
     opt: Shape,
     wins: Shape,
@@ -152,7 +140,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     let mut score = 0;
 
     for s in input.split('\n') {
-        let opponent = Rule::from_abc(s.chars().nth(0).unwrap()); // X, Y, Z
+        let opponent = Rule::from_abc(s.chars().next().unwrap()); // X, Y, Z
         let me = Rule::from_xyz(s.chars().nth(2).unwrap()); // A, B, C
         score += match_score(me.get_result(&opponent));
         score += base_score(me.opt);
@@ -187,11 +175,11 @@ pub fn part_two(input: &str) -> Option<u32> {
     let mut score = 0;
 
     for s in input.split('\n') {
-        let opponent = Rule::from_abc(s.chars().nth(0).unwrap());
+        let opponent = Rule::from_abc(s.chars().next().unwrap());
         let result = result_from_xyz(s.chars().nth(2).unwrap());
         let my_shape = opponent.to_get_result(&result);
 
-        println!(
+        advent_of_code::debug!(
             "{:?} vs {:?} => {:?}",
             &my_shape,
             &opponent.opt,
@@ -218,12 +206,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 2);
-        assert_eq!(part_one(&input), Some(11906));
+        assert_eq!(part_one(&input), Some(15));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 2);
-        assert_eq!(part_two(&input), Some(11186));
+        assert_eq!(part_two(&input), Some(12));
     }
 }
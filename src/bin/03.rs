@@ -1,4 +1,5 @@
-use std::{collections::HashSet, hash::Hash};
+use advent_of_code::helpers::letter_set::{Alphabet, LetterSet};
+use advent_of_code::helpers::parse::parse_lines;
 
 /*
 --- Day 3: Rucksack Reorganization ---
@@ -34,43 +35,18 @@ In the above example, the priority of the item type that appears in both compart
 Find the item type that appears in both compartments of each rucksack. What is the sum of the priorities of those item types?
 */
 
-fn char_to_priority(c: char) -> u32 {
-    // A-Z => 65 -> 90
-    // a-z => 97 -> 122
-    // Lowercase item types a through z have priorities 1 through 26.
-    // Uppercase item types A through Z have priorities 27 through 52.
-    if c.is_lowercase() {
-        c as u32 - 96
-    } else if c.is_uppercase() {
-        c as u32 - 64 + 26
-    } else {
-        0
-    }
-}
-
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut result: u32 = 0;
-
-    for s in input.split('\n') {
-        let mut set: HashSet<char> = HashSet::new();
+    let alphabet = Alphabet::rucksack();
 
-        let len = s.len();
-
-        for c in s.chars().take(len / 2) {
-            set.insert(c);
-        }
-
-        for c in s.chars().skip(len / 2) {
-            if set.contains(&c) {
-                let priority = char_to_priority(c);
-                // println!("{} -> {} (priority {})", &s, &c, priority);
-                result += priority;
-                break;
-            }
-        }
-    }
+    let priorities = parse_lines(input, |line| {
+        let (first, second) = line.split_at(line.len() / 2);
+        let common = LetterSet::parse(first, &alphabet)? & LetterSet::parse(second, &alphabet)?;
+        Ok(common.priority_sum(&alphabet))
+    })
+    .map_err(|e| eprintln!("{}", e))
+    .ok()?;
 
-    Some(result)
+    Some(priorities.iter().sum())
 }
 
 /*
@@ -100,38 +76,17 @@ Priorities for these items must still be found to organize the sticker attachmen
 Find the item type that corresponds to the badges of each three-Elf group. What is the sum of the priorities of those item types?
  */
 
-fn common_of_three_lines(a: &str, b: &str, c: &str) -> Option<char> {
-    let s1: HashSet<char> = a.chars().collect();
-    let s2: HashSet<char> = b.chars().collect();
-    let s3: HashSet<char> = c.chars().collect();
+pub fn part_two(input: &str) -> Option<u32> {
+    let alphabet = Alphabet::rucksack();
 
-    for ch in s1.intersection(&s2) {
-        if s3.contains(ch) {
-            return Some(*ch);
-        }
-    }
+    let rucksacks = parse_lines(input, |line| LetterSet::parse(line, &alphabet))
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
 
-    None
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut iter = input.split('\n').into_iter();
-    let mut result = 0;
-
-    loop {
-        let line1 = iter.next();
-        let line2 = iter.next();
-        let line3 = iter.next();
-
-        if line1.is_none() || line2.is_none() || line3.is_none() {
-            break;
-        }
-
-        result += match common_of_three_lines(line1.unwrap(), line2.unwrap(), line3.unwrap()) {
-            Some(ch) => char_to_priority(ch),
-            _ => 0,
-        }
-    }
+    let result = rucksacks
+        .chunks(3)
+        .map(|group| LetterSet::intersect_all(group.iter().copied()).priority_sum(&alphabet))
+        .sum();
 
     Some(result)
 }
@@ -149,12 +104,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 3);
-        assert_eq!(part_one(&input), Some(7845));
+        assert_eq!(part_one(&input), Some(157));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 3);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(70));
     }
 }
@@ -1,4 +1,5 @@
-use std::str::FromStr;
+use advent_of_code::helpers::interval::Interval;
+use advent_of_code::helpers::parse::{parse_lines, ParseError};
 
 /*
 --- Day 4: Camp Cleanup ---
@@ -43,55 +44,31 @@ Some of the pairs have noticed that one of their assignments fully contains the
 In how many assignment pairs does one range fully contain the other?
 */
 
+fn parse_pairs(input: &str) -> Option<Vec<(Interval, Interval)>> {
+    let pairs = parse_lines(input, |line| {
+        let (first, second) = line
+            .split_once(',')
+            .ok_or_else(|| ParseError::new(format!("expected a pair in {:?}", line)))?;
 
-#[derive(Debug)]
-struct Slice {
-    from: usize,
-    to: usize,
-}
-
-impl Slice {
-    // 17-18
-    fn from_str(s: &str) -> Slice {
-        let mut iter = s.split('-').into_iter();
+        Ok((first.parse()?, second.parse()?))
+    });
 
-        if let (Some(a), Some(b)) = (iter.next(), iter.next()) {
-            if let (Ok(from), Ok(to)) = (usize::from_str(a), usize::from_str(b)) {
-                return Slice { from: from, to: to };
-            }
+    match pairs {
+        Ok(pairs) => Some(pairs),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
         }
-
-        return Slice { from: 0, to: 0 };
-    }
-
-    fn is_overlap(&self, other: &Slice) -> bool {
-        (self.from <= other.from && self.to >= other.to)
-            || (self.from >= other.from && self.to <= other.to)
     }
-
-    fn is_partly_overlap(&self, other: &Slice) -> bool {
-        !(self.from > other.to || self.to < other.from)
-    }
-
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut count = 0;
-
-    for line in input.split('\n') {
-        let mut iter = line.split(',').into_iter();
+    let count = parse_pairs(input)?
+        .iter()
+        .filter(|(a, b)| a.contains_interval(b) || b.contains_interval(a))
+        .count();
 
-        if let (Some(first), Some(second)) = (iter.next(), iter.next()) {
-            let first_slice = Slice::from_str(first);
-            let second_slice = Slice::from_str(second);
-
-            if first_slice.is_overlap(&second_slice) {
-                count += 1;
-            }
-        }
-    }
-
-    return Some(count);
+    Some(count as u32)
 }
 
 /*
@@ -111,22 +88,12 @@ In how many assignment pairs do the ranges overlap?
 */
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut count = 0;
-
-    for line in input.split('\n') {
-        let mut iter = line.split(',').into_iter();
-
-        if let (Some(first), Some(second)) = (iter.next(), iter.next()) {
-            let first_slice = Slice::from_str(first);
-            let second_slice = Slice::from_str(second);
-
-            if first_slice.is_partly_overlap(&second_slice) {
-                count += 1;
-            }
-        }
-    }
+    let count = parse_pairs(input)?
+        .iter()
+        .filter(|(a, b)| a.overlaps(b))
+        .count();
 
-    return Some(count);
+    Some(count as u32)
 }
 
 fn main() {
@@ -142,12 +109,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 4);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), Some(2));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 4);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(4));
     }
 }
@@ -51,127 +51,452 @@ The Elves just need to know which crate will end up on top of each stack; in thi
 After the rearrangement procedure completes, what crate ends up on top of each stack?
 */
 
+use advent_of_code::helpers::{
+    cli::{self, Extras},
+    parse::{ParseError, Template},
+    search::bfs,
+};
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Stacks {
-    val: Vec<Vec<char>>,
+    /// Crate labels per stack, bottom first.
+    val: Vec<Vec<String>>,
 }
 
-impl Stacks {
-    fn new() -> Self {
-        Stacks { val: Vec::new() }
+/// Error at a 1-based line and column of the input.
+fn error_at(line: usize, column: usize, message: String) -> ParseError {
+    ParseError::new(format!("column {}: {}", column, message)).at_line(line)
+}
+
+/// Whitespace-separated words of a line with the 0-based char column they start at.
+/// Crate cells (`[A]`, `[AB]`, ...) are words that start with `[`.
+fn words(line: &str) -> Vec<(usize, String)> {
+    let mut out: Vec<(usize, String)> = vec![];
+    let mut in_word = false;
+
+    for (col, c) in line.chars().enumerate() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if in_word {
+            out.last_mut().unwrap().1.push(c);
+        } else {
+            out.push((col, String::from(c)));
+            in_word = true;
+        }
     }
 
-    fn add_line(&mut self, line: &str) {
-        // 3char<space>3char<space>
-        if line.contains('[') {
-            let mut chars = line.chars();
-            let mut i: usize = 0;
+    out
+}
 
-            loop {
-                if let (Some(_a), Some(b), Some(_c)) = (chars.next(), chars.next(), chars.next()) {
-                    // println!("push stack {} -> {}", i + 1, b);
+impl Stacks {
+    /// Parses a crate drawing: rows of `[label]` cells above a footer numbering the
+    /// stacks `1 2 3 ...`. The footer decides the number of stacks and where they
+    /// are; every crate must sit over its stack's number, one inside the other's columns.
+    /// `first_line` is the drawing's 1-based line number in the input, for errors.
+    fn parse(drawing: &str, first_line: usize) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = drawing.lines().collect();
+        let (footer, rows) = lines
+            .split_last()
+            .ok_or_else(|| ParseError::new("empty crate drawing").at_line(first_line))?;
+        let footer_line = first_line + rows.len();
 
-                    while self.val.len() < (i + 1) {
-                        self.val.push(Vec::new())
-                    }
+        // columns of every stack number, end exclusive.
+        let mut spans = vec![];
+        for (i, (col, word)) in words(footer).into_iter().enumerate() {
+            if word.parse() != Ok(i + 1) {
+                return Err(error_at(
+                    footer_line,
+                    col + 1,
+                    format!("expected stack number {}, found {:?}", i + 1, word),
+                ));
+            }
+            spans.push((col, col + word.chars().count()));
+        }
 
-                    if b != ' ' {
-                        self.val[i].push(b);
-                    }
+        if spans.is_empty() {
+            return Err(ParseError::new("missing stack numbers").at_line(footer_line));
+        }
 
-                    let space = chars.next();
-                    if space.is_none() {
-                        break;
-                    }
-                    i = i + 1
-                } else {
-                    break;
+        let mut stacks = Stacks {
+            val: vec![vec![]; spans.len()],
+        };
+
+        for (i, row) in rows.iter().enumerate().rev() {
+            let line = first_line + i;
+            let mut filled = vec![false; spans.len()];
+
+            for (col, word) in words(row) {
+                let label = word
+                    .strip_prefix('[')
+                    .and_then(|w| w.strip_suffix(']'))
+                    .filter(|label| !label.is_empty() && !label.contains(['[', ']']))
+                    .ok_or_else(|| {
+                        error_at(
+                            line,
+                            col + 1,
+                            format!("expected a crate like [A], found {:?}", word),
+                        )
+                    })?;
+
+                let end = col + word.chars().count();
+                let stack = spans
+                    .iter()
+                    .position(|&(start, stop)| {
+                        (col <= start && stop <= end) || (start <= col && end <= stop)
+                    })
+                    .ok_or_else(|| {
+                        error_at(
+                            line,
+                            col + 1,
+                            String::from("crate not above a stack number"),
+                        )
+                    })?;
+
+                if filled[stack] {
+                    return Err(error_at(
+                        line,
+                        col + 1,
+                        format!("second crate in stack {} on the same row", stack + 1),
+                    ));
+                }
+                // rows are read bottom up, so every crate rests on the one read before.
+                if stacks.val[stack].len() + 1 != rows.len() - i {
+                    return Err(error_at(
+                        line,
+                        col + 1,
+                        format!("crate {} floats above an empty space", word),
+                    ));
                 }
+
+                filled[stack] = true;
+                stacks.val[stack].push(String::from(label));
+            }
+        }
+
+        Ok(stacks)
+    }
+
+    /// Draws the stacks the way the puzzle does, top row first, numbered footer last.
+    /// Cells widen to fit the longest label or stack number; `parse` reads it back.
+    fn render(&self) -> String {
+        let height = self.val.iter().map(Vec::len).max().unwrap_or(0);
+        let label_width = self.val.iter().flatten().map(|l| l.chars().count()).max();
+        let width = (label_width.unwrap_or(1) + 2).max(self.val.len().to_string().len());
+
+        let center = |text: String| {
+            let pad = width - text.chars().count();
+            format!(
+                "{}{}{}",
+                " ".repeat(pad / 2),
+                text,
+                " ".repeat(pad - pad / 2)
+            )
+        };
+
+        let mut lines = vec![];
+
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .val
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => center(format!("[{}]", label)),
+                    None => " ".repeat(width),
+                })
+                .collect();
+            lines.push(row.join(" ").trim_end().to_string());
+        }
+
+        let footer: Vec<String> = (1..=self.val.len())
+            .map(|i| center(i.to_string()))
+            .collect();
+        lines.push(footer.join(" ").trim_end().to_string());
+
+        lines.join("\n")
+    }
+
+    /// Checks that both stacks of a move exist and that `from` holds enough crates.
+    fn check(&self, m: &Move) -> Result<(), MoveError> {
+        let error = |kind| MoveError {
+            line: None,
+            instruction: *m,
+            kind,
+        };
+
+        for stack in [m.from, m.to] {
+            if stack == 0 || stack > self.val.len() {
+                return Err(error(MoveErrorKind::NoSuchStack(stack)));
             }
         }
+
+        let has = self.val[m.from - 1].len();
+        if has < m.count {
+            return Err(error(MoveErrorKind::NotEnoughCrates { stack: m.from, has }));
+        }
+
+        Ok(())
+    }
+
+    /// Moves the top `count` crates of stack `from` onto stack `to` in one lift,
+    /// keeping their order. Stacks are numbered from 1; the move must have passed
+    /// `check`.
+    fn lift(&mut self, from: usize, to: usize, count: usize) {
+        let source = &mut self.val[from - 1];
+        let moved: Vec<String> = source.drain(source.len() - count..).collect();
+        self.val[to - 1].extend(moved);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, from, to) = Template::new("move {} from {} to {}").parse(s)?;
+        Ok(Move { count, from, to })
     }
+}
 
-    fn reverse_all(&mut self) {
-        // https://stackoverflow.com/questions/39622783/how-can-i-do-a-mutable-borrow-in-a-for-loop
-        // by default self.val is immutable
-        for stack in self.val.iter_mut() {
-            stack.reverse();
+impl Move {
+    /// The move carrying the crates back. With a CrateMover 9000 or 9001 it undoes
+    /// this one; `Crane::undo` works for every crane.
+    fn inverse(&self) -> Move {
+        Move {
+            count: self.count,
+            from: self.to,
+            to: self.from,
         }
     }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
 
-    fn command(&mut self, line: &str) {
-        let commands: Vec<&str> = line.split(' ').collect::<Vec<&str>>();
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MoveErrorKind {
+    NoSuchStack(usize),
+    NotEnoughCrates { stack: usize, has: usize },
+}
 
-        let amount: usize = commands[1].parse().unwrap();
-        let from: usize = commands[3].parse().unwrap();
-        let to: usize = commands[5].parse().unwrap();
+/// A move that can't be carried out on the current stacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MoveError {
+    /// 1-based line of the instruction in the input, if known.
+    line: Option<usize>,
+    instruction: Move,
+    kind: MoveErrorKind,
+}
 
-        println!("move {} elements from {} to {}", amount, from, to);
+impl MoveError {
+    fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+}
 
-        for _i in 0..amount {
-            let a = self.val[from - 1].pop();
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "{}: ", self.instruction)?;
 
-            if a.is_some() {
-                self.val[to - 1].push(a.unwrap());
+        match self.kind {
+            MoveErrorKind::NoSuchStack(stack) => write!(f, "there is no stack {}", stack),
+            MoveErrorKind::NotEnoughCrates { stack, has } => {
+                write!(f, "stack {} only holds {} crates", stack, has)
             }
         }
     }
+}
+
+impl Error for MoveError {}
+
+/// Why a procedure couldn't be run: a malformed input or an impossible move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RunError {
+    Parse(ParseError),
+    Move(MoveError),
+}
+
+impl From<ParseError> for RunError {
+    fn from(e: ParseError) -> Self {
+        RunError::Parse(e)
+    }
+}
 
-    fn command2(&mut self, line: &str) {
-        let commands: Vec<&str> = line.split(' ').collect::<Vec<&str>>();
+impl From<MoveError> for RunError {
+    fn from(e: MoveError) -> Self {
+        RunError::Move(e)
+    }
+}
 
-        let amount: usize = commands[1].parse().unwrap();
-        let from: usize = commands[3].parse().unwrap();
-        let to: usize = commands[5].parse().unwrap();
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(e) => write!(f, "{}", e),
+            RunError::Move(e) => write!(f, "{}", e),
+        }
+    }
+}
 
-        // println!("move {} elements from {} to {}", amount, from, to);
+impl Error for RunError {}
 
-        let count = self.val[from - 1].len();
+/// A crane model. New models only need to say how they split a move into lifts.
+trait Crane {
+    fn name(&self) -> String;
 
-        let a = count - amount;
+    /// Number of crates in each lift of a `count`-crate move, in order.
+    fn lifts(&self, count: usize) -> Vec<usize>;
 
-        let moving_part: Vec<char> = self.val[from - 1]
-            .drain(a..)
-            .collect();
+    /// Carries out a move, leaving the stacks untouched if it is impossible.
+    fn apply(&self, stacks: &mut Stacks, m: &Move) -> Result<(), MoveError> {
+        advent_of_code::debug!("{}: {}", self.name(), m);
+        stacks.check(m)?;
 
-        for part in moving_part {
-            self.val[to - 1].push(part);
+        for n in self.lifts(m.count) {
+            stacks.lift(m.from, m.to, n);
         }
 
-        println!("move {} elements from {} to {}", amount, from, to);
+        Ok(())
+    }
+
+    /// Reverses `m` right after it was applied, by replaying its lifts backwards.
+    fn undo(&self, stacks: &mut Stacks, m: &Move) -> Result<(), MoveError> {
+        advent_of_code::debug!("{}: undo {}", self.name(), m);
+        stacks.check(&m.inverse())?;
 
-        // for _i in 0..amount {
-        //     let a = self.val[from - 1].pop();
+        for n in self.lifts(m.count).into_iter().rev() {
+            stacks.lift(m.to, m.from, n);
+        }
 
-        //     if a.is_some() {
-        //         self.val[to - 1].push(a.unwrap());
-        //     }
-        // }
+        Ok(())
     }
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let mut input_mode_build_stack: bool = true;
-    let mut stacks = Stacks::new();
+/// Lifts one crate at a time, so moved crates end up in reverse order.
+struct CrateMover9000;
 
-    for line in input.split('\n') {
-        if input_mode_build_stack {
-            stacks.add_line(line);
-        } else {
-            stacks.command(line);
-            println!("{:?}", stacks.val);
-        }
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9000")
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        vec![1; count]
+    }
+}
 
-        if line.is_empty() {
-            stacks.reverse_all();
-            input_mode_build_stack = false;
+/// Lifts all crates at once, keeping their order.
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9001")
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        vec![count]
+    }
+}
+
+/// Lifts at most `capacity` crates at a time, splitting larger moves.
+struct LimitedCrane {
+    capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("crane lifting {} crates", self.capacity)
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        let mut lifts = vec![self.capacity; count / self.capacity];
+        if !count.is_multiple_of(self.capacity) {
+            lifts.push(count % self.capacity);
         }
+        lifts
+    }
+}
+
+/// `--crane 9000|9001`; the 9001 can be limited to `--capacity N` crates per lift.
+fn crane_model(model: u32, capacity: Option<usize>) -> Result<Box<dyn Crane>, String> {
+    match (model, capacity) {
+        (9000, None) => Ok(Box::new(CrateMover9000)),
+        (9000, Some(_)) => Err(String::from(
+            "--capacity only applies to --crane 9001, the 9000 lifts one crate at a time",
+        )),
+        (9001, None) => Ok(Box::new(CrateMover9001)),
+        (9001, Some(0)) => Err(String::from("capacity must be at least 1")),
+        (9001, Some(capacity)) => Ok(Box::new(LimitedCrane { capacity })),
+        (model, _) => Err(format!("unknown crane model {}", model)),
+    }
+}
+
+/// The starting stacks and every move with its 1-based line in the input.
+fn parse_input(input: &str) -> Result<(Stacks, Vec<(usize, Move)>), ParseError> {
+    let (drawing, moves) = input.split_once("\n\n").unwrap_or((input, ""));
+    let stacks = Stacks::parse(drawing, 1)?;
+    let first_move_line = drawing.lines().count() + 2;
+
+    let moves = moves
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let line_number = first_move_line + i;
+            line.parse()
+                .map(|m| (line_number, m))
+                .map_err(|e: ParseError| e.at_line(line_number))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((stacks, moves))
+}
+
+/// Builds the stacks and applies every move, calling `on_step` with the move line
+/// (empty for the starting drawing) and the stacks after it.
+fn run(
+    input: &str,
+    crane: &dyn Crane,
+    mut on_step: impl FnMut(&str, &Stacks),
+) -> Result<Stacks, RunError> {
+    let (mut stacks, moves) = parse_input(input)?;
+    on_step("", &stacks);
+
+    for (line, m) in moves {
+        crane.apply(&mut stacks, &m).map_err(|e| e.at_line(line))?;
+        advent_of_code::trace!("{:?}", stacks.val);
+        on_step(&m.to_string(), &stacks);
     }
-    let chars: String = stacks
+
+    Ok(stacks)
+}
+
+/// Top crate labels, left to right; an empty stack shows as a space.
+fn top_crates(stacks: &Stacks) -> String {
+    stacks
         .val
         .iter()
-        .map(|stack| stack[stack.len() - 1])
-        .collect();
-    Some(chars)
+        .map(|stack| stack.last().map_or(" ", String::as_str))
+        .collect()
+}
+
+pub fn part_one(input: &str) -> Option<String> {
+    let stacks = run(input, &CrateMover9000, |_, _| {})
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+    Some(top_crates(&stacks))
 }
 
 /*
@@ -221,34 +546,637 @@ Before the rearrangement process finishes, update your simulation so that the El
 */
 
 pub fn part_two(input: &str) -> Option<String> {
-    let mut input_mode_build_stack: bool = true;
-    let mut stacks = Stacks::new();
+    let stacks = run(input, &CrateMover9001, |_, _| {})
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+    Some(top_crates(&stacks))
+}
 
-    for line in input.split('\n') {
-        if input_mode_build_stack {
-            stacks.add_line(line);
-        } else {
-            stacks.command2(line);
-            println!("{:?}", &stacks.val);
+/// Every state drawn in the puzzle description for the example, starting drawing first.
+const EXAMPLE_STATES_9000: [&str; 5] = [
+    "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3",
+    "[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3",
+    "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3",
+    "        [Z]\n        [N]\n[M]     [D]\n[C]     [P]\n 1   2   3",
+    "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3",
+];
+
+const EXAMPLE_STATES_9001: [&str; 5] = [
+    "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3",
+    "[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3",
+    "        [D]\n        [N]\n    [C] [Z]\n    [M] [P]\n 1   2   3",
+    "        [D]\n        [N]\n[C]     [Z]\n[M]     [P]\n 1   2   3",
+    "        [D]\n        [N]\n        [Z]\n[M] [C] [P]\n 1   2   3",
+];
+
+/// Rendered states with the move that produced them, starting drawing first.
+fn replay(input: &str, crane: &dyn Crane) -> Result<Vec<(String, String)>, RunError> {
+    let mut frames = vec![];
+    run(input, crane, |line, stacks| {
+        frames.push((String::from(line), stacks.render()));
+    })?;
+    Ok(frames)
+}
+
+/// Steps whose rendered state differs from the expected drawing.
+fn check_replay(frames: &[(String, String)], expected: &[&str]) -> Vec<usize> {
+    let steps = frames.len().max(expected.len());
+
+    (0..steps)
+        .filter(|i| frames.get(*i).map(|(_, frame)| frame.as_str()) != expected.get(*i).copied())
+        .collect()
+}
+
+/// How one stack changed between two steps. Moves only ever touch the top of a
+/// stack, so the bottom `kept` crates are shared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StackChange {
+    stack: usize,
+    kept: usize,
+    removed: Vec<String>,
+    added: Vec<String>,
+}
+
+impl fmt::Display for StackChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let crates = |labels: &[String]| {
+            labels
+                .iter()
+                .map(|label| format!("[{}]", label))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        write!(f, "stack {}: kept {}", self.stack, self.kept)?;
+        if !self.removed.is_empty() {
+            write!(f, ", removed {}", crates(&self.removed))?;
+        }
+        if !self.added.is_empty() {
+            write!(f, ", added {}", crates(&self.added))?;
+        }
+        Ok(())
+    }
+}
+
+/// A procedure that can be replayed to any step, backwards as well as forwards. Step
+/// `k` is the state after the first `k` moves; step 0 is the starting drawing.
+struct History<'a> {
+    crane: &'a dyn Crane,
+    /// Every move with its line in the input.
+    moves: Vec<(usize, Move)>,
+    /// Stack heights at the start.
+    heights: Vec<usize>,
+    stacks: Stacks,
+    step: usize,
+}
+
+impl<'a> History<'a> {
+    /// Runs the whole procedure once to check every move, so seeking never fails.
+    /// The history starts out at step 0.
+    fn new(
+        start: Stacks,
+        moves: Vec<(usize, Move)>,
+        crane: &'a dyn Crane,
+    ) -> Result<Self, MoveError> {
+        let mut stacks = start.clone();
+        for (line, m) in &moves {
+            crane.apply(&mut stacks, m).map_err(|e| e.at_line(*line))?;
+        }
+
+        Ok(History {
+            crane,
+            heights: start.val.iter().map(Vec::len).collect(),
+            moves,
+            stacks: start,
+            step: 0,
+        })
+    }
+
+    fn steps(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Moves to `step`, clamped to the last one, applying or undoing moves on the way.
+    fn seek(&mut self, step: usize) -> &Stacks {
+        let step = step.min(self.steps());
+
+        while self.step < step {
+            let (_, m) = self.moves[self.step];
+            self.crane
+                .apply(&mut self.stacks, &m)
+                .expect("moves are checked by History::new");
+            self.step += 1;
+        }
+
+        while self.step > step {
+            self.step -= 1;
+            let (_, m) = self.moves[self.step];
+            self.crane
+                .undo(&mut self.stacks, &m)
+                .expect("undoing a move that was just applied");
+        }
+
+        &self.stacks
+    }
+
+    /// Stacks that differ between steps `a` and `b`, as seen going from `a` to `b`.
+    fn diff(&mut self, a: usize, b: usize) -> Vec<StackChange> {
+        let before = self.seek(a).clone();
+        let after = self.seek(b);
+
+        before
+            .val
+            .iter()
+            .zip(&after.val)
+            .enumerate()
+            .filter(|(_, (x, y))| x != y)
+            .map(|(i, (x, y))| {
+                let kept = x.iter().zip(y).take_while(|(a, b)| a == b).count();
+                StackChange {
+                    stack: i + 1,
+                    kept,
+                    removed: x[kept..].to_vec(),
+                    added: y[kept..].to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// The step whose move last put down the crate at `level` (0 is the bottom) of
+    /// `stack`, as it stands after `step` moves; `None` if it hasn't moved since the
+    /// start, an error if there's no such stack or no crate at that level. Only stack
+    /// heights are needed: a crate a move doesn't lift stays put.
+    fn last_touched(
+        &self,
+        step: usize,
+        stack: usize,
+        level: usize,
+    ) -> Result<Option<usize>, String> {
+        let step = step.min(self.steps());
+        if !(1..=self.heights.len()).contains(&stack) {
+            return Err(format!("no stack {}", stack));
+        }
+
+        let mut heights = vec![self.heights.clone()];
+
+        for (_, m) in &self.moves[..step] {
+            let mut next = heights.last().unwrap().clone();
+            next[m.from - 1] -= m.count;
+            next[m.to - 1] += m.count;
+            heights.push(next);
+        }
+
+        let height = heights[step][stack - 1];
+        if level >= height {
+            return Err(format!(
+                "stack {} holds {} crates after step {}, none at height {}",
+                stack,
+                height,
+                step,
+                level + 1
+            ));
+        }
+
+        Ok((1..=step).rev().find(|k| {
+            let (_, m) = self.moves[k - 1];
+            m.to == stack && level + m.count >= heights[*k][stack - 1]
+        }))
+    }
+}
+
+/// Order, bottom first, in which `crane` puts down the crates `moved` (the top of a
+/// stack, bottom first).
+fn landing_order<'s>(crane: &dyn Crane, moved: &'s [String]) -> Vec<&'s String> {
+    let mut rest = moved.len();
+    let mut out = vec![];
+
+    for n in crane.lifts(moved.len()) {
+        out.extend(&moved[rest - n..rest]);
+        rest -= n;
+    }
+
+    out
+}
+
+/// Builds the target bottom up. The bottom `locked[s]` crates of every stack are
+/// where the target wants them and stay put; everything above them is loose and can
+/// be moved around freely. Since only loose crates move, the loose crates are
+/// always exactly the ones still missing from the target.
+struct Planner<'a> {
+    crane: &'a dyn Crane,
+    target: &'a Stacks,
+    stacks: Stacks,
+    locked: Vec<usize>,
+    /// The stack being dug for, until it is complete.
+    current: Option<usize>,
+    moves: Vec<Move>,
+    /// The stacks before the last move, to see whether the next one can be merged.
+    previous: Stacks,
+}
+
+impl Planner<'_> {
+    fn loose(&self, s: usize) -> usize {
+        self.stacks.val[s].len() - self.locked[s]
+    }
+
+    fn complete(&self, s: usize) -> bool {
+        self.locked[s] == self.target.val[s].len()
+    }
+
+    /// Applies a move, merging it into the previous one when a single move between
+    /// the same stacks ends up the same.
+    fn push(&mut self, m: Move) {
+        let before = self.stacks.clone();
+        self.crane
+            .apply(&mut self.stacks, &m)
+            .expect("the planner only moves loose crates");
+
+        if let Some(last) = self.moves.last_mut() {
+            if (last.from, last.to) == (m.from, m.to) {
+                let merged = Move {
+                    count: last.count + m.count,
+                    ..m
+                };
+                let mut stacks = self.previous.clone();
+                if self.crane.apply(&mut stacks, &merged).is_ok() && stacks == self.stacks {
+                    *last = merged;
+                    return;
+                }
+            }
+        }
+
+        self.previous = before;
+        self.moves.push(m);
+    }
+
+    /// Where to drop loose crates: every crate buried there, and a stack that still
+    /// has to grow, cost a move later on.
+    fn junk_pile(&self, exclude: &[usize]) -> Option<usize> {
+        (0..self.stacks.val.len())
+            .filter(|w| !exclude.contains(w))
+            .min_by_key(|w| self.loose(*w) + usize::from(!self.complete(*w)))
+    }
+
+    /// The longest move of loose crates that continues an unfinished stack the way
+    /// the target has it.
+    fn best_direct(&self) -> Option<Move> {
+        let n = self.stacks.val.len();
+
+        (0..n)
+            .filter(|i| !self.complete(*i) && self.loose(*i) == 0)
+            .flat_map(|i| (0..n).filter(move |u| *u != i).map(move |u| (i, u)))
+            .flat_map(|(i, u)| {
+                let missing = self.target.val[i].len() - self.locked[i];
+                (1..=self.loose(u).min(missing)).map(move |m| (i, u, m))
+            })
+            .filter(|(i, u, m)| {
+                let stack = &self.stacks.val[*u];
+                let landed = landing_order(self.crane, &stack[stack.len() - m..]);
+                landed
+                    .into_iter()
+                    .eq(&self.target.val[*i][self.locked[*i]..][..*m])
+            })
+            .max_by_key(|(_, _, m)| *m)
+            .map(|(i, u, m)| Move {
+                count: m,
+                from: u + 1,
+                to: i + 1,
+            })
+    }
+
+    /// Clears the loose crates off stack `i`, or uncovers the next crate it needs.
+    fn dig(&mut self, i: usize) -> Result<(), String> {
+        if self.loose(i) > 0 {
+            let w = self
+                .junk_pile(&[i])
+                .ok_or("a single stack can't be rearranged")?;
+            self.push(Move {
+                count: self.loose(i),
+                from: i + 1,
+                to: w + 1,
+            });
+            return Ok(());
         }
 
-        if line.is_empty() {
-            stacks.reverse_all();
-            input_mode_build_stack = false;
+        let label = &self.target.val[i][self.locked[i]];
+        let (u, above) = (0..self.stacks.val.len())
+            .filter(|u| *u != i)
+            .filter_map(|u| {
+                let stack = &self.stacks.val[u];
+                (self.locked[u]..stack.len())
+                    .rev()
+                    .find(|k| stack[*k] == *label)
+                    .map(|k| (u, stack.len() - 1 - k))
+            })
+            .min_by_key(|(_, above)| *above)
+            .expect("the loose crates are the missing ones");
+        let w = self
+            .junk_pile(&[i, u])
+            .ok_or_else(|| format!("no third stack to dig [{}] out of stack {}", label, u + 1))?;
+        self.push(Move {
+            count: above,
+            from: u + 1,
+            to: w + 1,
+        });
+
+        Ok(())
+    }
+
+    /// Places crates wherever they can go straight away, and otherwise digs for one
+    /// unfinished stack (the one with the fewest loose crates) until it is complete.
+    /// Every dig is followed by a placement, so this ends.
+    fn solve(&mut self) -> Result<(), String> {
+        loop {
+            if let Some(m) = self.best_direct() {
+                self.locked[m.to - 1] += m.count;
+                self.push(m);
+                continue;
+            }
+
+            let current = self.current.filter(|i| !self.complete(*i)).or_else(|| {
+                (0..self.stacks.val.len())
+                    .filter(|i| !self.complete(*i))
+                    .min_by_key(|i| self.loose(*i))
+            });
+
+            match current {
+                Some(i) => {
+                    self.current = Some(i);
+                    self.dig(i)?;
+                }
+                None => return Ok(()),
+            }
         }
     }
-    let chars: String = stacks
+}
+
+/// The planner's moves from `start` to `target`. With fewer than three stacks it
+/// only succeeds when no crate has to be dug out.
+fn greedy_plan(start: &Stacks, target: &Stacks, crane: &dyn Crane) -> Result<Vec<Move>, String> {
+    let locked = start
         .val
         .iter()
-        .map(|stack| stack[stack.len() - 1])
+        .zip(&target.val)
+        .map(|(stack, wanted)| stack.iter().zip(wanted).take_while(|(a, b)| a == b).count())
         .collect();
-    Some(chars)
+
+    let mut planner = Planner {
+        crane,
+        target,
+        stacks: start.clone(),
+        locked,
+        current: None,
+        moves: vec![],
+        previous: start.clone(),
+    };
+    planner.solve()?;
+
+    Ok(planner.moves)
+}
+
+/// Number of states the exhaustive search may generate before giving up.
+const SEARCH_LIMIT: usize = 20_000;
+
+/// The move between two states one move apart.
+fn move_between(a: &Stacks, b: &Stacks) -> Move {
+    let heights = |s: usize| (a.val[s].len(), b.val[s].len());
+    let from = (0..a.val.len())
+        .find(|s| heights(*s).0 > heights(*s).1)
+        .unwrap();
+    let to = (0..a.val.len())
+        .find(|s| heights(*s).0 < heights(*s).1)
+        .unwrap();
+
+    Move {
+        count: heights(from).0 - heights(from).1,
+        from: from + 1,
+        to: to + 1,
+    }
+}
+
+/// The fewest moves from `start` to `target`, if a breadth-first search gets there
+/// within `SEARCH_LIMIT` states.
+fn shortest_plan(start: &Stacks, target: &Stacks, crane: &dyn Crane) -> Option<Vec<Move>> {
+    let n = start.val.len();
+    let mut generated = 0;
+
+    let successors = |stacks: &Stacks| {
+        let mut next = vec![];
+        if generated >= SEARCH_LIMIT {
+            return next;
+        }
+
+        for from in 1..=n {
+            for to in (1..=n).filter(|to| *to != from) {
+                for count in 1..=stacks.val[from - 1].len() {
+                    let mut moved = stacks.clone();
+                    if crane.apply(&mut moved, &Move { count, from, to }).is_ok() {
+                        next.push(moved);
+                    }
+                }
+            }
+        }
+
+        generated += next.len();
+        next
+    };
+
+    let path = bfs([start.clone()], successors, |s| s == target, true)?.path?;
+    Some(
+        path.windows(2)
+            .map(|w| move_between(&w[0], &w[1]))
+            .collect(),
+    )
+}
+
+/// A short procedure taking `start` to `target` with `crane`: the shortest one for
+/// small arrangements, else the planner's.
+fn plan(start: &Stacks, target: &Stacks, crane: &dyn Crane) -> Result<Vec<Move>, String> {
+    if start.val.len() != target.val.len() {
+        return Err(format!(
+            "the start has {} stacks, the target {}",
+            start.val.len(),
+            target.val.len()
+        ));
+    }
+
+    let labels = |stacks: &Stacks| {
+        let mut labels: Vec<String> = stacks.val.concat();
+        labels.sort();
+        labels
+    };
+    if labels(start) != labels(target) {
+        return Err(String::from("the target doesn't hold the same crates"));
+    }
+
+    match shortest_plan(start, target, crane) {
+        Some(moves) => Ok(moves),
+        None => greedy_plan(start, target, crane),
+    }
+}
+
+/// The stacks and moves as a puzzle input.
+fn procedure(start: &Stacks, moves: &[Move]) -> String {
+    let moves: Vec<String> = moves.iter().map(Move::to_string).collect();
+    format!("{}\n\n{}", start.render(), moves.join("\n"))
+}
+
+struct ReplayArgs {
+    replay: bool,
+    check: bool,
+    crane: u32,
+    capacity: Option<usize>,
+    at: Option<usize>,
+    diff: Option<(usize, usize)>,
+    touched: Option<String>,
+    plan: Option<String>,
+}
+
+fn parse_step_pair(s: &str) -> Result<(usize, usize), String> {
+    let (a, b) = s
+        .split_once(',')
+        .ok_or_else(|| format!("expected two steps like 1,3, got {:?}", s))?;
+    let step = |x: &str| x.parse().map_err(|_| format!("invalid step {:?}", x));
+    Ok((step(a)?, step(b)?))
+}
+
+impl Extras for ReplayArgs {
+    const ACTION: &'static str = "replay";
+
+    fn parse(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        Ok(ReplayArgs {
+            replay: args.contains("--replay"),
+            check: args.contains("--check"),
+            crane: args.opt_value_from_str("--crane")?.unwrap_or(9000),
+            capacity: args.opt_value_from_str("--capacity")?,
+            at: args.opt_value_from_str("--at")?,
+            diff: args.opt_value_from_fn("--diff", parse_step_pair)?,
+            touched: args.opt_value_from_str("--touched")?,
+            plan: args.opt_value_from_str("--plan")?,
+        })
+    }
+
+    /// `cargo solve 05 -- --replay [--crane 9001] [--capacity N]` prints the stacks after
+    /// every move.
+    /// `--check` replays the example with both cranes against the puzzle's drawings.
+    /// `--at K` prints the stacks after K moves, `--diff A,B` how they changed between
+    /// two steps, and `--touched LABEL` which move last put down every crate with that
+    /// label (as of `--at`, or the end).
+    /// `--plan FILE` prints a procedure taking the input's starting stacks to the drawing
+    /// in FILE, as a puzzle input, after checking it with the simulator.
+    fn run(self, input: &str) -> Result<(), String> {
+        if let Some(path) = &self.plan {
+            let crane = crane_model(self.crane, self.capacity)?;
+            let (start, _) = parse_input(input).map_err(|e| e.to_string())?;
+            let drawing = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let target = Stacks::parse(drawing.trim_end(), 1).map_err(|e| e.to_string())?;
+
+            let moves = plan(&start, &target, crane.as_ref())?;
+            let text = procedure(&start, &moves);
+            let end = run(&text, crane.as_ref(), |_, _| {}).map_err(|e| e.to_string())?;
+            if end != target {
+                return Err(format!("the plan ends in\n{}", end.render()));
+            }
+
+            println!(
+                "--- {} moves with the {} ---\n{}",
+                moves.len(),
+                crane.name(),
+                text
+            );
+        }
+
+        if self.at.is_some() || self.diff.is_some() || self.touched.is_some() {
+            let crane = crane_model(self.crane, self.capacity)?;
+            let (start, moves) = parse_input(input).map_err(|e| e.to_string())?;
+            let mut history =
+                History::new(start, moves, crane.as_ref()).map_err(|e| e.to_string())?;
+            let step = self.at.unwrap_or(history.steps()).min(history.steps());
+
+            if self.at.is_some() {
+                let caption = match step {
+                    0 => String::from("start"),
+                    k => format!("step {}: {}", k, history.moves[k - 1].1),
+                };
+                println!("--- {} ---\n{}\n", caption, history.seek(step).render());
+            }
+
+            if let Some((a, b)) = self.diff {
+                println!("--- step {} to step {} ---", a, b);
+                for change in history.diff(a, b) {
+                    println!("{}", change);
+                }
+            }
+
+            if let Some(label) = self.touched {
+                let stacks = history.seek(step).clone();
+                let crates = stacks.val.iter().enumerate().flat_map(|(s, stack)| {
+                    (0..stack.len())
+                        .filter(|level| stack[*level] == label)
+                        .map(move |level| (s + 1, level))
+                });
+
+                for (stack, level) in crates {
+                    let place = format!("[{}] at height {} of stack {}", label, level + 1, stack);
+                    match history.last_touched(step, stack, level)? {
+                        Some(k) => {
+                            let (line, m) = history.moves[k - 1];
+                            println!("{}: step {}, line {}: {}", place, k, line, m);
+                        }
+                        None => println!("{}: not moved", place),
+                    }
+                }
+            }
+        }
+
+        if self.replay {
+            let crane = crane_model(self.crane, self.capacity)?;
+            for (line, frame) in replay(input, crane.as_ref()).map_err(|e| e.to_string())? {
+                let caption = if line.is_empty() { "start" } else { &line };
+                println!("--- {} ---\n{}\n", caption, frame);
+            }
+        }
+
+        if self.check {
+            let example = advent_of_code::read_file("examples", 5);
+
+            let cranes: [(&dyn Crane, _); 2] = [
+                (&CrateMover9000, &EXAMPLE_STATES_9000),
+                (&CrateMover9001, &EXAMPLE_STATES_9001),
+            ];
+
+            for (crane, expected) in cranes {
+                let frames = replay(&example, crane).map_err(|e| e.to_string())?;
+                let mismatches = check_replay(&frames, expected);
+
+                if mismatches.is_empty() {
+                    println!("{}: all {} states match", crane.name(), frames.len());
+                }
+
+                for step in mismatches {
+                    let actual = frames.get(step).map_or("<missing>", |(_, frame)| frame);
+                    let wanted = expected.get(step).copied().unwrap_or("<missing>");
+                    println!(
+                        "{} differs after step {}:\n{}\n--- expected ---\n{}\n",
+                        crane.name(),
+                        step,
+                        actual,
+                        wanted
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 5);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+
+    cli::run_extras::<ReplayArgs>(input);
 }
 
 #[cfg(test)]
@@ -266,4 +1194,331 @@ mod tests {
         let input = advent_of_code::read_file("examples", 5);
         assert_eq!(part_two(&input), Some(String::from("MCD")));
     }
+
+    #[test]
+    fn test_replay_matches_drawings() {
+        let input = advent_of_code::read_file("examples", 5);
+
+        let frames = replay(&input, &CrateMover9000).unwrap();
+        assert_eq!(frames[2].0, "move 3 from 1 to 3");
+        assert!(check_replay(&frames, &EXAMPLE_STATES_9000).is_empty());
+
+        let frames = replay(&input, &CrateMover9001).unwrap();
+        assert!(check_replay(&frames, &EXAMPLE_STATES_9001).is_empty());
+        assert_eq!(check_replay(&frames, &EXAMPLE_STATES_9000), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_cranes() {
+        let input = advent_of_code::read_file("examples", 5);
+
+        // lifting one crate at a time is a CrateMover 9000, lifting everything a 9001.
+        let one = run(&input, &LimitedCrane { capacity: 1 }, |_, _| {}).unwrap();
+        assert_eq!(top_crates(&one), "CMZ");
+        let all = run(&input, &LimitedCrane { capacity: 3 }, |_, _| {}).unwrap();
+        assert_eq!(top_crates(&all), "MCD");
+
+        // "move 3 from 1 to 3" lifts [N] [D] together first, then [Z] on top.
+        let two = run(&input, &LimitedCrane { capacity: 2 }, |_, _| {}).unwrap();
+        assert_eq!(two.val[2], vec!["P", "N", "D", "Z"]);
+        assert_eq!(LimitedCrane { capacity: 2 }.lifts(5), vec![2, 2, 1]);
+
+        let m: Move = "move 3 from 1 to 3".parse().unwrap();
+        assert_eq!(
+            m,
+            Move {
+                count: 3,
+                from: 1,
+                to: 3
+            }
+        );
+        assert_eq!(m.to_string(), "move 3 from 1 to 3");
+
+        let broken = input.replace("move 2 from 2 to 1", "move two from 2 to 1");
+        assert!(matches!(
+            run(&broken, &CrateMover9000, |_, _| {}),
+            Err(RunError::Parse(e)) if e.line == Some(8)
+        ));
+    }
+
+    #[test]
+    fn test_crane_model() {
+        let name = |model, capacity| crane_model(model, capacity).map(|crane| crane.name());
+
+        assert_eq!(name(9000, None), Ok(String::from("CrateMover 9000")));
+        assert_eq!(
+            name(9001, Some(2)),
+            Ok(String::from("crane lifting 2 crates"))
+        );
+        assert_eq!(
+            name(9001, Some(0)),
+            Err(String::from("capacity must be at least 1"))
+        );
+        assert_eq!(
+            name(9002, None),
+            Err(String::from("unknown crane model 9002"))
+        );
+
+        let args: Vec<std::ffi::OsString> = ["--replay", "--capacity", "2"]
+            .iter()
+            .map(Into::into)
+            .collect();
+        let extras = cli::parse::<ReplayArgs>(args).unwrap().unwrap();
+        let input = advent_of_code::read_file("examples", 5);
+        assert_eq!(
+            extras.run(&input),
+            Err(String::from(
+                "--capacity only applies to --crane 9001, the 9000 lifts one crate at a time"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_impossible_moves() {
+        let input = advent_of_code::read_file("examples", 5);
+        let err = |from: &str, to: &str| {
+            run(&input.replace(from, to), &CrateMover9001, |_, _| {})
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            err("move 3 from 1 to 3", "move 4 from 1 to 3"),
+            "line 7: move 4 from 1 to 3: stack 1 only holds 3 crates"
+        );
+        assert_eq!(
+            err("move 2 from 2 to 1", "move 2 from 2 to 4"),
+            "line 8: move 2 from 2 to 4: there is no stack 4"
+        );
+        assert_eq!(
+            err("move 1 from 2 to 1", "move 1 from 0 to 1"),
+            "line 6: move 1 from 0 to 1: there is no stack 0"
+        );
+
+        // a failed move leaves the stacks as they were.
+        let mut stacks = Stacks::parse(EXAMPLE_STATES_9000[0], 1).unwrap();
+        let m = Move {
+            count: 3,
+            from: 2,
+            to: 3,
+        };
+        assert!(CrateMover9000.apply(&mut stacks, &m).is_ok());
+        assert_eq!(top_crates(&stacks), "N M");
+        let m = Move {
+            count: 1,
+            from: 2,
+            to: 1,
+        };
+        assert_eq!(
+            CrateMover9000.apply(&mut stacks, &m).unwrap_err().kind,
+            MoveErrorKind::NotEnoughCrates { stack: 2, has: 0 }
+        );
+        assert_eq!(top_crates(&stacks), "N M");
+    }
+
+    #[test]
+    fn test_history() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (start, moves) = parse_input(&input).unwrap();
+        assert_eq!(moves[1], (7, "move 3 from 1 to 3".parse().unwrap()));
+
+        let mut history = History::new(start.clone(), moves.clone(), &CrateMover9000).unwrap();
+        for step in [4, 1, 3, 0, 2] {
+            assert_eq!(history.seek(step).render(), EXAMPLE_STATES_9000[step]);
+        }
+        assert_eq!(history.seek(10).render(), EXAMPLE_STATES_9000[4]);
+
+        let changes: Vec<String> = history.diff(1, 3).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "stack 1: kept 0, removed [Z] [N] [D], added [C] [M]",
+                "stack 2: kept 0, removed [M] [C]",
+                "stack 3: kept 1, added [D] [N] [Z]",
+            ]
+        );
+        assert!(history.diff(2, 2).is_empty());
+
+        // after the last move: [C] [M] [P D N Z].
+        assert_eq!(history.last_touched(4, 3, 0), Ok(None));
+        assert_eq!(history.last_touched(4, 3, 3), Ok(Some(2)));
+        assert_eq!(history.last_touched(4, 2, 0), Ok(Some(4)));
+        assert_eq!(history.last_touched(4, 1, 0), Ok(Some(3)));
+        assert_eq!(history.last_touched(1, 1, 2), Ok(Some(1)));
+
+        assert_eq!(
+            history.last_touched(4, 0, 0),
+            Err(String::from("no stack 0"))
+        );
+        assert_eq!(
+            history.last_touched(4, 4, 0),
+            Err(String::from("no stack 4"))
+        );
+        assert_eq!(
+            history.last_touched(4, 1, 1),
+            Err(String::from(
+                "stack 1 holds 1 crates after step 4, none at height 2"
+            ))
+        );
+        assert!(history.last_touched(0, 3, 1).is_err());
+
+        // undoing works for cranes whose inverse isn't a single move of their own.
+        let crane = LimitedCrane { capacity: 2 };
+        let mut history = History::new(start.clone(), moves.clone(), &crane).unwrap();
+        let end = history.seek(4).clone();
+        assert_eq!(history.seek(0), &start);
+        assert_eq!(history.seek(4), &end);
+
+        // stack 2 holds three crates, so the fourth "move 1 from 2 to 1" fails.
+        let broken = (6..10).map(|line| (line, moves[0].1)).collect();
+        assert_eq!(
+            History::new(start, broken, &CrateMover9000)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string(),
+            "line 9: move 1 from 2 to 1: stack 2 only holds 0 crates"
+        );
+    }
+
+    #[test]
+    fn test_plan() {
+        let start = Stacks::parse(EXAMPLE_STATES_9000[0], 1).unwrap();
+        let cases: [(&dyn Crane, &str, usize); 2] = [
+            (&CrateMover9000, EXAMPLE_STATES_9000[4], 3),
+            (&CrateMover9001, EXAMPLE_STATES_9001[4], 4),
+        ];
+
+        for (crane, target, shortest) in cases {
+            let target = Stacks::parse(target, 1).unwrap();
+
+            for (from, to) in [(&start, &target), (&target, &start)] {
+                let moves = plan(from, to, crane).unwrap();
+                assert_eq!(moves.len(), shortest, "{}", crane.name());
+
+                let greedy = greedy_plan(from, to, crane).unwrap();
+                for moves in [moves, greedy] {
+                    let end = run(&procedure(from, &moves), crane, |_, _| {}).unwrap();
+                    assert_eq!(&end, to);
+                }
+            }
+        }
+
+        // the plan is a puzzle input again.
+        let target = Stacks::parse(EXAMPLE_STATES_9000[4], 1).unwrap();
+        let moves = plan(&start, &target, &CrateMover9000).unwrap();
+        assert_eq!(
+            procedure(&start, &moves),
+            format!(
+                "{}\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 1 from 2 to 1",
+                EXAMPLE_STATES_9000[0]
+            )
+        );
+    }
+
+    #[test]
+    fn test_plan_large() {
+        // too big for the exhaustive search: 9 stacks of 5 crates, dealt out
+        // round-robin in reverse.
+        let labels: Vec<String> = (0..45)
+            .map(|i| format!("{}{}", (b'A' + i / 5) as char, i % 5))
+            .collect();
+        let start = Stacks {
+            val: labels.chunks(5).map(<[String]>::to_vec).collect(),
+        };
+        let mut target = Stacks {
+            val: vec![vec![]; 9],
+        };
+        for (i, label) in labels.iter().rev().enumerate() {
+            target.val[i % 9].push(label.clone());
+        }
+
+        let cranes: [&dyn Crane; 2] = [&CrateMover9000, &CrateMover9001];
+        for crane in cranes {
+            let moves = plan(&start, &target, crane).unwrap();
+            let end = run(&procedure(&start, &moves), crane, |_, _| {}).unwrap();
+            assert_eq!(end, target);
+        }
+
+        let two = Stacks::parse("[B]\n[A] [C]\n 1   2", 1).unwrap();
+        let err = |drawing: &str| {
+            plan(&two, &Stacks::parse(drawing, 1).unwrap(), &CrateMover9000).unwrap_err()
+        };
+        // with a 9000 and two stacks, the crates keep their order around the stacks.
+        assert_eq!(
+            err("[C]\n[A]\n[B]\n 1   2"),
+            "no third stack to dig [B] out of stack 2"
+        );
+        assert_eq!(
+            err("[A] [B] [C]\n 1   2   3"),
+            "the start has 2 stacks, the target 3"
+        );
+        assert_eq!(
+            err("[A] [D]\n 1   2"),
+            "the target doesn't hold the same crates"
+        );
+    }
+
+    #[test]
+    fn test_drawing_round_trip() {
+        for drawing in EXAMPLE_STATES_9000.iter().chain(&EXAMPLE_STATES_9001) {
+            let stacks = Stacks::parse(drawing, 1).unwrap();
+            assert_eq!(&stacks.render(), drawing);
+        }
+
+        let stacks = Stacks {
+            val: vec![
+                vec![String::from("AB"), String::from("C")],
+                vec![],
+                vec![String::from("LONG")],
+                vec![String::from("x")],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![String::from("Z")],
+                vec![String::from("Q"), String::from("RS"), String::from("T")],
+            ],
+        };
+        let drawing = stacks.render();
+        assert_eq!(
+            drawing.lines().last(),
+            Some("  1      2      3      4      5      6      7      8      9      10     11")
+        );
+        assert_eq!(Stacks::parse(&drawing, 1), Ok(stacks));
+    }
+
+    #[test]
+    fn test_malformed_drawings() {
+        let err = |drawing: &str| Stacks::parse(drawing, 1).unwrap_err().to_string();
+
+        assert_eq!(
+            err("[A] [B\n 1   2"),
+            "line 1: column 5: expected a crate like [A], found \"[B\""
+        );
+        assert_eq!(
+            err("[A]\n 1   3"),
+            "line 2: column 6: expected stack number 2, found \"3\""
+        );
+        assert_eq!(
+            err("[A]\n    [B]\n 1   2"),
+            "line 1: column 1: crate [A] floats above an empty space"
+        );
+        assert_eq!(
+            err("[A][B]\n 1   2"),
+            "line 1: column 1: expected a crate like [A], found \"[A][B]\""
+        );
+        assert_eq!(
+            err("[A]"),
+            "line 1: column 1: expected stack number 1, found \"[A]\""
+        );
+        assert_eq!(
+            err("  [A]\n 1   2"),
+            "line 1: column 3: crate not above a stack number"
+        );
+        assert_eq!(
+            err("[A]   [B]\n 1   2"),
+            "line 1: column 7: crate not above a stack number"
+        );
+    }
 }
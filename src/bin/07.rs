@@ -1,226 +1,211 @@
-// use std::borrow::{Borrow, BorrowMut};
-// build the tree, count the file size, sum them
-use std::collections::HashMap;
-use std::rc::Rc;
-use std::cell::{RefCell};
-
-type FileNodeRef = Rc<RefCell<FileNode>>;
-
-#[derive(Debug)]
-struct FileNode {
-    name: String,
-    is_dir: bool,
-    size: u32,
-    children: HashMap<String, FileNodeRef>,
+use advent_of_code::helpers::{
+    cli::{self, Extras},
+    vfs::{FileSystem, Kind, NodeId, ROOT},
+};
+
+fn parse_input(input: &str) -> Option<FileSystem> {
+    FileSystem::from_transcript(input)
+        .map_err(|e| eprintln!("{}", e))
+        .ok()
 }
 
-impl FileNode {
-    fn root() -> Self {
-        Self::dir("/")
-    }
-
-    fn dir(name: &str) -> Self {
-        FileNode {
-            name: String::from(name),
-            size: 0,
-            children: HashMap::new(),
-            is_dir: true
-        }
-    }
-
-    fn file(name: &str, size: u32) -> Self {
-        FileNode {
-            name: String::from(name),
-            size: size,
-            children: HashMap::new(),
-            is_dir: false
-        }
-    }
-
-    fn get_children_dirs (&self) -> Vec<FileNodeRef> {
-        let mut dirs = vec![];
+pub fn part_one(input: &str) -> Option<u64> {
+    let fs = parse_input(input)?;
 
-        for (_key, value) in self.children.iter() {
-            // first * deref of a normal ref, second * deref of Rc. Rust is amazing language
-            let item = &**value;
+    let total_size = fs
+        .dirs()
+        .map(|dir| fs.size(dir))
+        .filter(|size| *size <= 100000)
+        .sum();
 
-            if item.borrow().is_dir {
-                dirs.push(value.clone());
-                let mut children_dirs = item.borrow().get_children_dirs();
-                dirs.append(&mut children_dirs);
-            }
-        }
-
-        return dirs
-    }
+    Some(total_size)
 }
 
+const TOTAL_SIZE: u64 = 70000000;
+const NEEDED_SIZE: u64 = 30000000;
 
-struct Shell {
-    // root takes the real ownership of the data (as a FileTree)
-    root: FileNodeRef,
-
-    // dir_history takes a mutable reference of a FileNode
-    dir_history: Vec<FileNodeRef>,
+/// How much has to be deleted to make room for the update.
+fn space_to_free(fs: &FileSystem) -> Option<u64> {
+    let free_size = TOTAL_SIZE.checked_sub(fs.size(ROOT))?;
+    Some(NEEDED_SIZE.saturating_sub(free_size))
 }
 
-impl<'a> Shell {
-    fn init() -> Self {
-        let root = FileNode::root();
+pub fn part_two(input: &str) -> Option<u64> {
+    let fs = parse_input(input)?;
+    let deleted_size = space_to_free(&fs)?;
 
-        Shell {
-            root: Rc::new(RefCell::new(root)),
-            dir_history: vec![],
-        }
-    }
+    fs.dirs()
+        .map(|dir| fs.size(dir))
+        .filter(|size| *size >= deleted_size)
+        .min()
+}
 
-    fn init_with_input (input: &str) -> Self {
-        let mut shell = Shell::init();
-
-        for line in input.lines() {
-            // println!("{}", line);
-
-            if line.starts_with('$') {
-                // Possible Commands: [$, cd, /] or [$, ls]
-                let commands: Vec<&str> = line.split(' ').collect();
-
-                if commands[1] == "cd" {
-                    shell.cd(commands[2]);
-                } else if commands[1] == "ls" {
-                    // don't need do anything for ls
-                } else {
-                    println!("unknown commands {:?}", commands);
-                }
-            } else {
-                // dir <dir_name>
-                let outputs: Vec<&str> = line.split(' ').collect();
-
-                if outputs[0] == "dir" {
-                    shell.mkdir(outputs[1]);
-                } else {
-                    shell.touch(outputs[0].parse().unwrap_or(0), outputs[1])
-                }
-            }
-        }
+#[derive(Debug, Clone, Copy, Default)]
+struct ReportOptions {
+    /// Root is depth 0.
+    max_depth: Option<usize>,
+    min_size: u64,
+}
 
-        shell
+impl ReportOptions {
+    fn shows(&self, fs: &FileSystem, id: NodeId, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth <= max) && fs.size(id) >= self.min_size
     }
+}
 
-    fn pwd(&'a self) -> FileNodeRef {
-        self.dir_history.last().unwrap_or(&self.root).clone()
-    }
+/// Children ordered largest first, ties by name.
+fn children_by_size(fs: &FileSystem, id: NodeId) -> Vec<NodeId> {
+    let mut children: Vec<NodeId> = fs.children(id).collect();
+    children.sort_by(|a, b| {
+        fs.size(*b)
+            .cmp(&fs.size(*a))
+            .then_with(|| fs.node(*a).name.cmp(&fs.node(*b).name))
+    });
+    children
+}
 
-    fn cd(&mut self, path: &str) {
-        if path == ".." {
-            self.dir_history.pop();
+/// The puzzle's `- / (dir)` listing, with directory sizes added.
+fn tree(fs: &FileSystem, options: ReportOptions) -> String {
+    fn visit(
+        fs: &FileSystem,
+        id: NodeId,
+        depth: usize,
+        options: ReportOptions,
+        out: &mut Vec<String>,
+    ) {
+        if !options.shows(fs, id, depth) {
             return;
         }
 
-        // why it's so dirty here?
-        let binding = self.pwd();
-        let binding = (*binding).borrow();
-        let file_node = binding.children.get(path);
-
-        if file_node.is_some() {
-            let node = file_node.unwrap();
-            self.dir_history.push(node.clone())
+        let node = fs.node(id);
+        let kind = match node.kind {
+            Kind::Dir(_) => "dir",
+            Kind::File(_) => "file",
+        };
+        out.push(format!(
+            "{}- {} ({}, size={})",
+            "  ".repeat(depth),
+            node.name,
+            kind,
+            fs.size(id)
+        ));
+
+        for child in children_by_size(fs, id) {
+            visit(fs, child, depth + 1, options, out);
         }
     }
 
-    fn create_file (&mut self, size: u32, filename: &str) {
-        let value = FileNode::file(filename, size);
-        let binding = self.pwd();
-        let mut binding = (*binding).borrow_mut();
+    let mut out = vec![];
+    visit(fs, ROOT, 0, options, &mut out);
+    out.join("\n")
+}
 
-        binding.children.insert(
-            String::from(filename),
-            Rc::new(RefCell::new(value))
-        );
+/// Sizes the way `du -h` prints them: plain bytes below 1K, one decimal below 10.
+fn human_size(size: u64) -> String {
+    if size < 1024 {
+        return size.to_string();
     }
 
-    fn touch(&mut self, size: u32, filename: &str) {
-        self.create_file(size, filename);
-
-        // increase the size of the file and their parents (including root node)
-        for dir in self.dir_history.iter() {
-            // WTF is `&**`
-            let item = &**dir;
-
-            let mut m = item.borrow_mut();
-            m.size += size;
-
-            // println!("increase {} -> {}", m.name, m.size);
+    let mut value = size as f64;
+    let mut unit = ' ';
+    for u in ['K', 'M', 'G', 'T'] {
+        if value < 1024.0 {
+            break;
         }
-
-        let mut root = (*self.root).borrow_mut();
-        root.size += size;
+        value /= 1024.0;
+        unit = u;
     }
 
-    fn mkdir(&mut self, dirname: &str) {
-        let value = FileNode::dir(dirname);
-        let binding = self.pwd();
-        let mut binding = (*binding).borrow_mut();
-
-        binding.children.insert(
-            String::from(dirname),
-            Rc::new(RefCell::new(value))
-        );
+    // like du, round up rather than to nearest.
+    if value < 10.0 {
+        format!("{:.1}{}", (value * 10.0).ceil() / 10.0, unit)
+    } else {
+        format!("{:.0}{}", value.ceil(), unit)
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let shell = Shell::init_with_input(input);
+/// `du -h` style listing of directories, largest first.
+fn du(fs: &FileSystem, options: ReportOptions) -> String {
+    let mut dirs: Vec<(usize, NodeId)> = fs
+        .walk(ROOT)
+        .filter(|(depth, id)| fs.node(*id).is_dir() && options.shows(fs, *id, *depth))
+        .collect();
 
-    let mut total_size = 0;
+    dirs.sort_by(|(_, a), (_, b)| fs.size(*b).cmp(&fs.size(*a)).then(a.cmp(b)));
 
-    let dirs = (*shell.root).borrow().get_children_dirs();
-    for dir in dirs {
-        let node = (*dir).borrow();
-
-        // println!("{} {}", node.name, node.size);
-        if node.size < 100000 {
-            total_size += node.size;
-        }
-    }
-
-    if shell.root.borrow().size < 100000 {
-        total_size += shell.root.borrow().size
-    }
-
-    Some(total_size)
+    dirs.iter()
+        .map(|(_, id)| format!("{}\t{}", human_size(fs.size(*id)), fs.path(*id)))
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let shell = Shell::init_with_input(input);
+struct ReportArgs {
+    tree: bool,
+    du: bool,
+    explain: bool,
+    options: ReportOptions,
+}
 
-    const TOTAL_SIZE: u32 = 70000000;
-    const NEEDED_SIZE: u32 = 30000000;
+impl Extras for ReportArgs {
+    const ACTION: &'static str = "print report";
+
+    fn parse(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        Ok(ReportArgs {
+            tree: args.contains("--tree"),
+            du: args.contains("--du"),
+            explain: args.contains("--explain"),
+            options: ReportOptions {
+                max_depth: args.opt_value_from_str("--max-depth")?,
+                min_size: args.opt_value_from_str("--min-size")?.unwrap_or(0),
+            },
+        })
+    }
 
-    let root_size = shell.root.borrow().size;
-    let deleted_size = NEEDED_SIZE - (TOTAL_SIZE - root_size);
+    /// `cargo solve 07 -- --tree` / `--du` print the parsed filesystem, both accept
+    /// `--max-depth N` and `--min-size N`. `--explain` lists the part two candidates.
+    fn run(self, input: &str) -> Result<(), String> {
+        if !(self.tree || self.du || self.explain) {
+            return Ok(());
+        }
 
-    let mut size = root_size;
+        let fs = FileSystem::from_transcript(input).map_err(|e| e.to_string())?;
 
-    if size < deleted_size {
-        return None
-    }
+        if self.tree {
+            println!("{}", tree(&fs, self.options));
+        }
 
-    for dir in (*shell.root).borrow().get_children_dirs() {
-        let node = (*dir).borrow();
+        if self.du {
+            println!("{}", du(&fs, self.options));
+        }
 
-        // println!("{} {}", node.name, node.size);
-        if node.size > deleted_size && node.size < size {
-            size = node.size
+        if self.explain {
+            let used = fs.size(ROOT);
+            let needed = space_to_free(&fs).ok_or("filesystem is larger than the disk")?;
+            println!(
+                "used {} of {}, {} free; the update needs {} more.",
+                used,
+                TOTAL_SIZE,
+                TOTAL_SIZE - used,
+                needed
+            );
+            println!("directories large enough to delete, smallest last:");
+            let options = ReportOptions {
+                min_size: needed,
+                ..self.options
+            };
+            println!("{}", du(&fs, options));
         }
-    }
 
-    Some(size)
+        Ok(())
+    }
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 7);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+
+    cli::run_extras::<ReportArgs>(input);
 }
 
 #[cfg(test)]
@@ -238,4 +223,45 @@ mod tests {
         let input = advent_of_code::read_file("examples", 7);
         assert_eq!(part_two(&input), Some(24933642));
     }
+
+    #[test]
+    fn test_tree() {
+        let input = advent_of_code::read_file("examples", 7);
+        let fs = FileSystem::from_transcript(&input).unwrap();
+
+        let full = tree(&fs, ReportOptions::default());
+        assert_eq!(full.lines().count(), 14);
+        assert!(full.contains("\n      - i (file, size=584)"));
+
+        let options = ReportOptions {
+            max_depth: Some(1),
+            min_size: 100000,
+        };
+        assert_eq!(
+            tree(&fs, options),
+            "- / (dir, size=48381165)
+  - d (dir, size=24933642)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)"
+        );
+    }
+
+    #[test]
+    fn test_du() {
+        let input = advent_of_code::read_file("examples", 7);
+        let fs = FileSystem::from_transcript(&input).unwrap();
+
+        assert_eq!(
+            du(&fs, ReportOptions::default()),
+            "47M\t/\n24M\t/d\n93K\t/a\n584\t/a/e"
+        );
+
+        let options = ReportOptions {
+            max_depth: Some(1),
+            min_size: 8381165,
+        };
+        assert_eq!(du(&fs, options), "47M\t/\n24M\t/d");
+
+        assert_eq!(human_size(1536), "1.5K");
+    }
 }
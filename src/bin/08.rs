@@ -26,11 +26,7 @@ impl TreeMap {
         let height = self.v.len() as i32;
         let width = self.v.first().unwrap().len() as i32;
 
-        if x < 0 || y < 0 || x > (width - 1) || y > (height - 1) {
-            true
-        } else {
-            false
-        }
+        x < 0 || y < 0 || x > (width - 1) || y > (height - 1)
     }
 
     fn is_visible (&self, x: usize, y: usize) -> bool {
@@ -55,7 +51,7 @@ impl TreeMap {
             }
         }
 
-        return false
+        false
     }
 
     fn calculate_score (&self, x: usize, y: usize) -> u32 {
@@ -87,7 +83,7 @@ impl TreeMap {
             output *= sum;
         }
 
-        return output
+        output
     }
 
 }
@@ -1,6 +1,12 @@
 // head
 
+use advent_of_code::helpers::{
+    cli::{self, Extras},
+    viz::{Cast, Cell, Sparse, Terminal},
+};
 use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct Position {
@@ -38,6 +44,8 @@ struct Rope {
     // tail: Position,
     knots: Vec<Position>,
     tail_visited: HashSet<Position>,
+    // positions of all knots after every single step, when recording.
+    snapshots: Option<Vec<Vec<Position>>>,
 }
 
 impl Rope {
@@ -51,11 +59,18 @@ impl Rope {
         }
 
         Rope {
-            knots: knots,
-            tail_visited: tail_visited,
+            knots,
+            tail_visited,
+            snapshots: None,
         }
     }
 
+    fn recording(knots_count: usize) -> Self {
+        let mut rope = Rope::init(knots_count);
+        rope.snapshots = Some(vec![rope.knots.clone()]);
+        rope
+    }
+
     fn read_input(&mut self, input: &str) {
         for line in input.lines() {
             let mut iter = line.split(' ');
@@ -69,7 +84,7 @@ impl Rope {
                 "R" => self.move_right(steps),
                 "U" => self.move_up(steps),
                 "D" => self.move_down(steps),
-                _ => println!("Unknown command: {:?}", command),
+                _ => eprintln!("Unknown command: {:?}", command),
             }
 
             // println!("{:?}", self.knots);
@@ -97,13 +112,17 @@ impl Rope {
             if !first.is_touching(second) {
                 second.keep_up(&first);
                 if n == (len - 1) {
-                    println!("tail {:?} visit {:?}", n, second.clone());
+                    advent_of_code::trace!("tail {:?} visit {:?}", n, second);
                     self.tail_visited.insert(second.clone());
                 }
             }
 
             n += 1;
         }
+
+        if let Some(snapshots) = self.snapshots.as_mut() {
+            snapshots.push(self.knots.clone());
+        }
     }
 
     fn move_left(&mut self, times: usize) {
@@ -135,6 +154,104 @@ impl Rope {
     }
 }
 
+/// The puzzle draws the head as `H`, then the knots as `1..9`, or just `T` for a
+/// two-knot rope.
+fn knot_label(i: usize, len: usize) -> char {
+    match i {
+        0 => 'H',
+        _ if len == 2 => 'T',
+        _ => char::from_digit(i as u32, 36).unwrap_or('?'),
+    }
+}
+
+/// Smallest view that contains every knot in every snapshot (and the start).
+fn snapshot_bounds(snapshots: &[Vec<Position>]) -> ((i64, i64), (i64, i64)) {
+    let all = snapshots.iter().flatten();
+    let x0 = all.clone().map(|p| p.x).min().unwrap_or(0).min(0) as i64;
+    let x1 = all.clone().map(|p| p.x).max().unwrap_or(0).max(0) as i64;
+    let y0 = all.clone().map(|p| p.y).min().unwrap_or(0).min(0) as i64;
+    let y1 = all.map(|p| p.y).max().unwrap_or(0).max(0) as i64;
+    ((x0, y0), (x1, y1))
+}
+
+/// One frame in the puzzle's notation. Earlier knots cover later ones, `s` marks the start.
+fn render_knots(knots: &[Position], bounds: ((i64, i64), (i64, i64))) -> Sparse {
+    let mut frame = Sparse::new('.').y_up().with_bounds(bounds.0, bounds.1);
+
+    for (i, knot) in knots.iter().enumerate() {
+        let color = if i == 0 {
+            (255, 80, 80)
+        } else {
+            (255, 200, 80)
+        };
+        frame.insert_under(
+            (knot.x as i64, knot.y as i64),
+            Cell::colored(knot_label(i, knots.len()), color),
+        );
+    }
+    frame.insert_under((0, 0), Cell::new('s'));
+
+    frame
+}
+
+struct AnimateArgs {
+    animate: bool,
+    cast: Option<String>,
+    knots: usize,
+    fps: u32,
+}
+
+impl Extras for AnimateArgs {
+    const ACTION: &'static str = "export animation";
+
+    fn parse(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        Ok(AnimateArgs {
+            animate: args.contains("--animate"),
+            cast: args.opt_value_from_str("--cast")?,
+            knots: args.opt_value_from_str("--knots")?.unwrap_or(10),
+            fps: args.opt_value_from_str("--fps")?.unwrap_or(10),
+        })
+    }
+
+    /// `cargo solve 09 -- --animate` replays the rope in the terminal,
+    /// `--cast rope.cast` writes an asciinema recording. `--knots` and `--fps` tune both.
+    fn run(self, input: &str) -> Result<(), String> {
+        if !self.animate && self.cast.is_none() {
+            return Ok(());
+        }
+
+        let mut rope = Rope::recording(self.knots.max(1));
+        rope.read_input(input);
+        let snapshots = rope.snapshots.unwrap_or_default();
+        let bounds = snapshot_bounds(&snapshots);
+
+        let caption = |step: usize| format!("step {}/{}", step, snapshots.len() - 1);
+
+        if let Some(path) = self.cast {
+            let mut cast = Cast::new(self.fps);
+            for (step, knots) in snapshots.iter().enumerate() {
+                cast.push(&render_knots(knots, bounds), &caption(step));
+            }
+
+            let file = File::create(&path).map_err(|e| e.to_string())?;
+            cast.write(&mut BufWriter::new(file))
+                .map_err(|e| e.to_string())?;
+            println!("Wrote {} frames to \"{}\"", cast.len(), path);
+        }
+
+        if self.animate {
+            let mut terminal = Terminal::stdout(self.fps);
+            for (step, knots) in snapshots.iter().enumerate() {
+                terminal
+                    .draw_with_caption(&render_knots(knots, bounds), &caption(step))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
     let mut rope = Rope::init(2);
     // println!("Knots: {:?}", rope.knots);
@@ -155,11 +272,14 @@ fn main() {
 
     // 2616 too low, 2658
     advent_of_code::solve!(2, part_two, input);
+
+    cli::run_extras::<AnimateArgs>(input);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::helpers::viz::to_text;
 
     #[test]
     fn test_part_one() {
@@ -185,4 +305,19 @@ U 20",
         let input = advent_of_code::read_file("examples", 9);
         assert_eq!(part_two(&input), Some(1));
     }
+
+    #[test]
+    fn test_snapshots() {
+        let mut rope = Rope::recording(10);
+        rope.read_input("R 4\nU 4");
+
+        let snapshots = rope.snapshots.unwrap();
+        assert_eq!(snapshots.len(), 9);
+
+        let frame = render_knots(&snapshots[4], ((0, 0), (5, 4)));
+        assert_eq!(to_text(&frame), "......\n......\n......\n......\n4321H.\n");
+
+        let frame = render_knots(&snapshots[8], snapshot_bounds(&snapshots));
+        assert_eq!(to_text(&frame), "....H\n....1\n..432\n.5...\n6....\n");
+    }
 }
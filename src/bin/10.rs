@@ -1,9 +1,148 @@
-pub fn part_one(input: &str) -> Option<u32> {
-    None
+use advent_of_code::helpers::{
+    ocr,
+    parse::{parse_lines, ParseError},
+    viz::{to_text, Cell, Render},
+};
+use std::str::FromStr;
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+impl Instruction {
+    /// Number of cycles the instruction takes to complete.
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["noop"] => Ok(Instruction::Noop),
+            ["addx", v] => v
+                .parse()
+                .map(Instruction::Addx)
+                .map_err(|_| ParseError::new(format!("invalid addx operand {:?}", v))),
+            _ => Err(ParseError::new(format!("unknown instruction {:?}", s))),
+        }
+    }
+}
+
+fn parse_program(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_lines(input.trim_end(), str::parse)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cpu {
+    x: i32,
+    /// Number of completed cycles.
+    cycle: usize,
+}
+
+impl Cpu {
+    fn new() -> Self {
+        Cpu { x: 1, cycle: 0 }
+    }
+
+    /// Runs one instruction. `on_cycle(cycle, x)` is called *during* each of its cycles
+    /// (1-based), i.e. before the instruction has taken effect.
+    fn step(&mut self, instruction: Instruction, on_cycle: &mut impl FnMut(usize, i32)) {
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            on_cycle(self.cycle, self.x);
+        }
+
+        if let Instruction::Addx(v) = instruction {
+            self.x += v;
+        }
+    }
+
+    fn run(&mut self, program: &[Instruction], mut on_cycle: impl FnMut(usize, i32)) {
+        for instruction in program {
+            self.step(*instruction, &mut on_cycle);
+            advent_of_code::trace!("{:?} -> x={} cycle={}", instruction, self.x, self.cycle);
+        }
+    }
+}
+
+struct Crt {
+    pixels: [[bool; SCREEN_WIDTH]; SCREEN_HEIGHT],
+}
+
+impl Crt {
+    fn new() -> Self {
+        Crt {
+            pixels: [[false; SCREEN_WIDTH]; SCREEN_HEIGHT],
+        }
+    }
+
+    /// Draws the pixel for `cycle`; it is lit if the 3 pixel wide sprite centered on `x`
+    /// covers it. Cycles past the last pixel are ignored.
+    fn draw(&mut self, cycle: usize, x: i32) {
+        let pos = cycle - 1;
+        let (row, col) = (pos / SCREEN_WIDTH, pos % SCREEN_WIDTH);
+
+        if row < SCREEN_HEIGHT {
+            self.pixels[row][col] = (col as i32 - x).abs() <= 1;
+        }
+    }
+
+    fn grid(&self) -> Vec<Vec<bool>> {
+        self.pixels.iter().map(|row| row.to_vec()).collect()
+    }
+}
+
+impl Render for Crt {
+    fn size(&self) -> (usize, usize) {
+        (SCREEN_WIDTH, SCREEN_HEIGHT)
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Cell {
+        Cell::new(if self.pixels[y][x] { '#' } else { '.' })
+    }
+}
+
+/// Sum of `cycle * x` during cycles 20, 60, 100, ...
+pub fn part_one(input: &str) -> Option<i32> {
+    let program = parse_program(input).map_err(|e| eprintln!("{}", e)).ok()?;
+
+    let mut sum = 0;
+    Cpu::new().run(&program, |cycle, x| {
+        if cycle % 40 == 20 {
+            advent_of_code::debug!("cycle {}: x={}", cycle, x);
+            sum += cycle as i32 * x;
+        }
+    });
+
+    Some(sum)
+}
+
+fn screen(program: &[Instruction]) -> Crt {
+    let mut crt = Crt::new();
+    Cpu::new().run(program, |cycle, x| crt.draw(cycle, x));
+    crt
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+/// The letters drawn on the screen. When they can't be read, the screen is printed
+/// so they can still be read by eye.
+pub fn part_two(input: &str) -> Option<String> {
+    let program = parse_program(input).map_err(|e| eprintln!("{}", e)).ok()?;
+    let crt = screen(&program);
+
+    ocr::read_grid(&crt.grid())
+        .map_err(|e| eprintln!("{}\n\n{}", e, to_text(&crt)))
+        .ok()
 }
 
 fn main() {
@@ -19,12 +158,42 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 10);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), Some(13140));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 10);
+        // the example draws a test pattern, not letters.
         assert_eq!(part_two(&input), None);
     }
+
+    #[test]
+    fn test_screen() {
+        let input = advent_of_code::read_file("examples", 10);
+        let program = parse_program(&input).unwrap();
+        assert_eq!(
+            to_text(&screen(&program)),
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let program = parse_program("noop\naddx 3\naddx -5").unwrap();
+
+        let mut samples = vec![];
+        let mut cpu = Cpu::new();
+        cpu.run(&program, |cycle, x| samples.push((cycle, x)));
+
+        assert_eq!(samples, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu, Cpu { x: -1, cycle: 5 });
+        assert!(parse_program("noop\naddx x").is_err_and(|e| e.line == Some(2)));
+    }
 }
@@ -1,9 +1,221 @@
-pub fn part_one(input: &str) -> Option<u32> {
-    None
+use advent_of_code::helpers::{
+    expr::{EvalError, Expr},
+    parse::{list, paragraphs, Paragraph, ParseError, Template},
+    top_k::TopK,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Monkey {
+    items: Vec<u64>,
+    /// The new worry level, in terms of the `old` one.
+    operation: Expr,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+impl Monkey {
+    /// Worry level after inspecting an item; `modulus` reduces it without changing
+    /// which monkey it is thrown to.
+    fn inspect(&self, old: u64, modulus: Option<u64>) -> Result<u64, EvalError> {
+        let vars = |name: &str| (name == "old").then_some(old);
+
+        match modulus {
+            Some(m) => self.operation.eval_mod(&vars, m),
+            None => self.operation.eval(&vars),
+        }
+    }
+
+    /// Which monkey an item with the given worry level is thrown to.
+    fn target(&self, worry: u64) -> usize {
+        if worry.is_multiple_of(self.divisor) {
+            self.if_true
+        } else {
+            self.if_false
+        }
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+fn parse_operation(text: &str) -> Result<Expr, ParseError> {
+    let (expr,): (String,) = Template::new("new = {}").parse(text)?;
+    let expr = Expr::parse(&expr)?;
+
+    match expr.variables().iter().find(|name| **name != "old") {
+        Some(name) => Err(ParseError::new(format!("unknown variable {:?}", name))),
+        None => Ok(expr),
+    }
+}
+
+fn parse_monkey(paragraph: &Paragraph, index: usize) -> Result<Monkey, ParseError> {
+    let lines: Vec<&str> = paragraph.lines().map(str::trim).collect();
+    let line = |i: usize| {
+        lines
+            .get(i)
+            .copied()
+            .ok_or_else(|| ParseError::new("incomplete monkey").at_line(paragraph.line + i))
+    };
+    let at = |i: usize| move |e: ParseError| e.at_line(paragraph.line + i);
+
+    let (id,): (usize,) = Template::new("Monkey {}:").parse(line(0)?).map_err(at(0))?;
+    if id != index {
+        return Err(
+            ParseError::new(format!("expected monkey {}, got {}", index, id))
+                .at_line(paragraph.line),
+        );
+    }
+
+    let (items,): (String,) = Template::new("Starting items: {}")
+        .parse(line(1)?)
+        .map_err(at(1))?;
+    let (operation,): (String,) = Template::new("Operation: {}")
+        .parse(line(2)?)
+        .map_err(at(2))?;
+    let (divisor,) = Template::new("Test: divisible by {}")
+        .parse(line(3)?)
+        .map_err(at(3))?;
+    let (if_true,) = Template::new("If true: throw to monkey {}")
+        .parse(line(4)?)
+        .map_err(at(4))?;
+    let (if_false,) = Template::new("If false: throw to monkey {}")
+        .parse(line(5)?)
+        .map_err(at(5))?;
+
+    if divisor == 0 {
+        return Err(ParseError::new("divisible by 0").at_line(paragraph.line + 3));
+    }
+
+    Ok(Monkey {
+        items: list(&items, ",").map_err(at(1))?,
+        operation: parse_operation(&operation).map_err(at(2))?,
+        divisor,
+        if_true,
+        if_false,
+    })
+}
+
+fn parse_input(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = paragraphs(input)
+        .iter()
+        .enumerate()
+        .map(|(i, p)| parse_monkey(p, i))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (i, monkey) in monkeys.iter().enumerate() {
+        if monkey.if_true.max(monkey.if_false) >= monkeys.len() {
+            return Err(ParseError::new(format!(
+                "monkey {} throws to a monkey that does not exist",
+                i
+            )));
+        }
+    }
+
+    Ok(monkeys)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relief {
+    /// Worry levels are divided by this after every inspection.
+    DivideBy(u64),
+    None,
+}
+
+struct Simulation {
+    monkeys: Vec<Monkey>,
+    inspections: Vec<u64>,
+    relief: Relief,
+    // without relief, worry levels are kept modulo the LCM of all divisors. That leaves
+    // every divisibility test unchanged and keeps `old * old` well inside u64. It can't
+    // be combined with relief, since dividing doesn't commute with the reduction.
+    modulus: u64,
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Simulation {
+    fn new(monkeys: Vec<Monkey>, relief: Relief) -> Result<Self, ParseError> {
+        let modulus = monkeys
+            .iter()
+            .try_fold(1_u64, |acc, m| {
+                (acc / gcd(acc, m.divisor)).checked_mul(m.divisor)
+            })
+            .ok_or_else(|| {
+                ParseError::new("least common multiple of the divisors overflows u64")
+            })?;
+
+        Ok(Simulation {
+            inspections: vec![0; monkeys.len()],
+            monkeys,
+            relief,
+            modulus,
+        })
+    }
+
+    fn round(&mut self) -> Result<(), EvalError> {
+        for i in 0..self.monkeys.len() {
+            let items = std::mem::take(&mut self.monkeys[i].items);
+            self.inspections[i] += items.len() as u64;
+
+            for item in items {
+                let monkey = &self.monkeys[i];
+
+                let worry = match self.relief {
+                    Relief::DivideBy(d) => monkey.inspect(item, None)? / d,
+                    Relief::None => monkey.inspect(item, Some(self.modulus))?,
+                };
+
+                let target = monkey.target(worry);
+                self.monkeys[target].items.push(worry);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run(&mut self, rounds: usize) -> Result<(), EvalError> {
+        for round in 1..=rounds {
+            self.round()?;
+            advent_of_code::trace!("after round {}: {:?}", round, self.inspections);
+        }
+
+        Ok(())
+    }
+
+    /// Product of the two highest inspection counts.
+    fn monkey_business(&self) -> u64 {
+        TopK::of(2, self.inspections.iter().copied())
+            .into_sorted_vec()
+            .iter()
+            .product()
+    }
+}
+
+fn simulate(input: &str, relief: Relief, rounds: usize) -> Option<u64> {
+    let monkeys = parse_input(input).map_err(|e| eprintln!("{}", e)).ok()?;
+
+    let mut simulation = Simulation::new(monkeys, relief)
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+    simulation
+        .run(rounds)
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+    advent_of_code::debug!("inspections: {:?}", simulation.inspections);
+
+    Some(simulation.monkey_business())
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    simulate(input, Relief::DivideBy(3), 20)
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    simulate(input, Relief::None, 10_000)
 }
 
 fn main() {
@@ -19,12 +231,57 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 11);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), Some(10605));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 11);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(2713310158));
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = advent_of_code::read_file("examples", 11);
+        let monkeys = parse_input(&input).unwrap();
+
+        assert_eq!(
+            monkeys[2],
+            Monkey {
+                items: vec![79, 60, 97],
+                operation: Expr::parse("old * old").unwrap(),
+                divisor: 13,
+                if_true: 1,
+                if_false: 3,
+            }
+        );
+
+        let mut simulation = Simulation::new(monkeys, Relief::DivideBy(3)).unwrap();
+        assert_eq!(simulation.modulus, 23 * 19 * 13 * 17);
+        simulation.run(20).unwrap();
+        assert_eq!(simulation.inspections, vec![101, 95, 7, 105]);
+
+        let primes = input
+            .replacen("divisible by 23", "divisible by 4294967291", 1)
+            .replacen("divisible by 19", "divisible by 4294967279", 1);
+        let monkeys = parse_input(&primes).unwrap();
+        assert_eq!(
+            Simulation::new(monkeys, Relief::None)
+                .err()
+                .map(|e| e.to_string()),
+            Some(String::from(
+                "least common multiple of the divisors overflows u64"
+            ))
+        );
+
+        let broken = input.replacen("new = old * 19", "new = old ^ 19", 1);
+        let err = parse_input(&broken).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3: column 5: unexpected character '^'"
+        );
+
+        let broken = input.replacen("new = old * 19", "new = older * 19", 1);
+        assert_eq!(parse_input(&broken).unwrap_err().line, Some(3));
     }
 }
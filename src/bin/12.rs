@@ -1,145 +1,495 @@
-// use rayon::prelude::*;
-use std::{
-    cmp::Ordering::Equal,
-    collections::{HashMap, VecDeque},
+use advent_of_code::helpers::{
+    cli::{self, Extras},
+    parse::ParseError,
+    search::{self, bfs},
+    viz::{heat, to_ansi, to_text, write_ppm, Cell},
 };
+use std::{cmp::Ordering, collections::HashMap, fs::File, io::BufWriter, str::FromStr};
 
+/// (row, col)
 type Pos = (usize, usize);
-type Unvisited = HashMap<Pos, Node>;
-type Trails = VecDeque<Pos>;
 
 #[derive(Clone, Debug)]
-struct Node {
-    val: usize,
-    pos: (usize, usize),
-    dist: f32,
-}
-
-fn parse_input(input: &str) -> (Unvisited, Pos, Pos, Trails) {
-    let mut unvisited = HashMap::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    let mut trails = VecDeque::new();
-
-    for (i, line) in input.lines().enumerate() {
-        for (j, mut char) in line.chars().enumerate() {
-            let (pos, dist) = ((i, j), f32::INFINITY);
-
-            if char == 'S' {
-                (start, char) = (pos, 'a');
-                // char = 'a'
-            } else if char == 'E' {
-                (end, char) = (pos, 'z');
-            }
+struct HeightMap {
+    /// Elevation per cell, 0 for 'a' through 25 for 'z'.
+    heights: Vec<Vec<u8>>,
+    start: Pos,
+    end: Pos,
+}
+
+impl HeightMap {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut heights = vec![];
+        let mut start = None;
+        let mut end = None;
 
-            if char == 'a' {
-                trails.push_back(pos);
+        for (i, line) in input.lines().enumerate() {
+            let mut row = vec![];
+
+            for (j, char) in line.chars().enumerate() {
+                let height = match char {
+                    'S' => {
+                        start = Some((i, j));
+                        0
+                    }
+                    'E' => {
+                        end = Some((i, j));
+                        25
+                    }
+                    'a'..='z' => char as u8 - b'a',
+                    _ => {
+                        return Err(ParseError::new(format!(
+                            "unexpected {:?} at column {}",
+                            char,
+                            j + 1
+                        ))
+                        .at_line(i + 1))
+                    }
+                };
+                row.push(height);
             }
 
-            let val = (char as u8 - 'a' as u8) as usize;
-            unvisited.insert(pos, Node { pos, dist, val });
+            heights.push(row);
         }
+
+        Ok(HeightMap {
+            heights,
+            start: start.ok_or_else(|| ParseError::new("no start (S) found"))?,
+            end: end.ok_or_else(|| ParseError::new("no end (E) found"))?,
+        })
+    }
+
+    fn height(&self, pos: Pos) -> Option<u8> {
+        self.heights.get(pos.0)?.get(pos.1).copied()
+    }
+
+    /// Cells next to `pos` that can be climbed to: at most one higher.
+    fn steps(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let max = self.heights[pos.0][pos.1] + 1;
+        neighbors(pos).filter(move |n| self.height(*n).is_some_and(|h| h <= max))
     }
 
-    (unvisited, start, end, trails)
+    /// Cells next to `pos` that can climb to it: the steps of a reverse search.
+    fn steps_back(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let height = self.heights[pos.0][pos.1];
+        neighbors(pos).filter(move |n| self.height(*n).is_some_and(|h| height <= h + 1))
+    }
 }
 
-/// An implementation of Dijkstra's shortest path algorithm, how fun!
-/// https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm#Algorithm
-/// 1. Mark all nodes as unvisited and create the unvisited set.
-/// 2. Assign a tentative distance to each node. Zero for starting node and infinity
-///    for all others.
-/// 3. Calculate a tentative distance for all unvisited neighbours. Set the smaller
-///    value of newly calc'ed and already set distance.
-/// 4. Mark current node as visited, remove from unvisited set.
-/// 5. Base case. If end node has been visited or if the smalllest distance in the
-///    unvisited set is infinity, then the algorithm is finished.
-/// 6. Else, select unvisited node with smallest distance and loop back to 3.
-fn dijkstra(unvisited: &mut Unvisited, start: Pos, end: Pos) -> Option<f32> {
-    fn update_neighbor(unvisited: &mut Unvisited, cur: &Node, pos: Pos) {
-        if let Some(neigh) = unvisited.get_mut(&(pos)) {
-            // Steepness check.
-            if neigh.val <= cur.val + 1 {
-                neigh.dist = neigh.dist.min(cur.dist + 1.);
-            }
+fn neighbors(pos: Pos) -> impl Iterator<Item = Pos> {
+    let (r, c) = pos;
+    [
+        Some((r, c + 1)),
+        Some((r + 1, c)),
+        c.checked_sub(1).map(|c| (r, c)),
+        r.checked_sub(1).map(|r| (r, c)),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Route {
+    dist: u32,
+    /// From start to end, both included.
+    path: Vec<Pos>,
+}
+
+impl Route {
+    fn start(&self) -> Pos {
+        self.path[0]
+    }
+
+    /// Checks that the route takes only legal steps from `start` to `end`.
+    fn validate(&self, map: &HeightMap, start: Pos, end: Pos) -> Result<(), String> {
+        if self.path.first() != Some(&start) || self.path.last() != Some(&end) {
+            return Err(String::from("does not lead from start to end"));
+        }
+        if self.path.len() != self.dist as usize + 1 {
+            return Err(format!(
+                "{} steps, but a distance of {}",
+                self.path.len() - 1,
+                self.dist
+            ));
+        }
+
+        match self
+            .path
+            .windows(2)
+            .find(|step| !map.steps(step[0]).any(|n| n == step[1]))
+        {
+            Some(step) => Err(format!("illegal step {:?} -> {:?}", step[0], step[1])),
+            None => Ok(()),
         }
     }
+}
 
-    let mut res = None;
-    let mut next_pos = start;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Strategy {
+    Dijkstra,
+    Bfs,
+    RelaxingDfs,
+}
 
-    loop {
-        let cur = unvisited.remove(&next_pos).unwrap();
+impl Strategy {
+    const ALL: [Strategy; 3] = [Strategy::Dijkstra, Strategy::Bfs, Strategy::RelaxingDfs];
 
-        let pos = cur.pos;
+    fn name(&self) -> &'static str {
+        match self {
+            Strategy::Dijkstra => "dijkstra",
+            Strategy::Bfs => "bfs",
+            Strategy::RelaxingDfs => "relaxing-dfs",
+        }
+    }
 
-        update_neighbor(unvisited, &cur, (pos.0, pos.1 + 1));
-        update_neighbor(unvisited, &cur, (pos.0 + 1, pos.1));
-        if pos.1 > 0 {
-            update_neighbor(unvisited, &cur, (pos.0, pos.1 - 1));
+    fn shortest_route(&self, map: &HeightMap) -> Option<Route> {
+        match self {
+            Strategy::Dijkstra => dijkstra(map),
+            Strategy::Bfs => breadth_first(map),
+            Strategy::RelaxingDfs => relaxing_dfs(map),
         }
-        if pos.0 > 0 {
-            update_neighbor(unvisited, &cur, (pos.0 - 1, pos.1));
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown strategy {:?}, expected dijkstra, bfs or relaxing-dfs",
+                    s
+                )
+            })
+    }
+}
+
+/// Every step costs 1; the strategy is here to cross-check `search::dijkstra` against BFS.
+fn dijkstra(map: &HeightMap) -> Option<Route> {
+    let found = search::dijkstra(
+        [map.start],
+        |pos| map.steps(*pos).map(|n| (n, 1)).collect::<Vec<_>>(),
+        |pos| *pos == map.end,
+        true,
+    )?;
+
+    Some(Route {
+        dist: found.cost,
+        path: found.path.unwrap_or_default(),
+    })
+}
+
+fn breadth_first(map: &HeightMap) -> Option<Route> {
+    let found = bfs(
+        [map.start],
+        |pos| map.steps(*pos).collect::<Vec<_>>(),
+        |pos| *pos == map.end,
+        true,
+    )?;
+
+    Some(Route {
+        dist: found.cost as u32,
+        path: found.path.unwrap_or_default(),
+    })
+}
+
+/// Steps to `end` from every cell that can reach it, by a depth-first search backwards
+/// from `end` that keeps lowering each cell's distance until nothing improves. No cell
+/// is final before the search ends: it is searched again whenever a shorter way to it
+/// turns up.
+///
+/// It replaces the memoized DFS of 12.old.rs, which cached cells that were still being
+/// calculated and so ignored routes through them on maps with cycles.
+///
+/// Cells can be revisited many times, so this is by far the slowest strategy; it is
+/// here to cross-check the others.
+fn distances_to_end(map: &HeightMap) -> HashMap<Pos, u32> {
+    let mut best = HashMap::from([(map.end, 0)]);
+    let mut stack = vec![(map.end, 0)];
+
+    while let Some((pos, dist)) = stack.pop() {
+        // a shorter way to `pos` was found after this entry was pushed.
+        if best[&pos] < dist {
+            continue;
         }
 
-        if pos == end {
-            res = Some(cur.dist);
-            break;
+        for n in map.steps_back(pos) {
+            if best.get(&n).is_none_or(|d| dist + 1 < *d) {
+                best.insert(n, dist + 1);
+                stack.push((n, dist + 1));
+            }
         }
+    }
 
-        if unvisited
-            .iter()
-            .find(|(_, n)| n.dist != f32::INFINITY)
-            .is_none()
-        {
-            break;
+    best
+}
+
+fn relaxing_dfs(map: &HeightMap) -> Option<Route> {
+    let best = distances_to_end(map);
+    let dist = *best.get(&map.start)?;
+
+    // every cell on a shortest route has a step to a cell one closer to the end.
+    let mut path = vec![map.start];
+    for remaining in (0..dist).rev() {
+        let cur = *path.last().unwrap();
+        path.push(
+            map.steps(cur)
+                .find(|n| best.get(n) == Some(&remaining))
+                .unwrap(),
+        );
+    }
+
+    Some(Route { dist, path })
+}
+
+/// Runs every strategy on the same map and describes every way they disagree,
+/// including routes that don't hold up.
+fn cross_check(map: &HeightMap) -> (Vec<(Strategy, Option<Route>)>, Vec<String>) {
+    let results: Vec<(Strategy, Option<Route>)> = Strategy::ALL
+        .into_iter()
+        .map(|strategy| (strategy, strategy.shortest_route(map)))
+        .collect();
+
+    let mut problems = vec![];
+    let (first, first_route) = &results[0];
+
+    for (strategy, route) in &results {
+        if let Some(route) = route {
+            if let Err(e) = route.validate(map, map.start, map.end) {
+                problems.push(format!("{}: invalid route: {}", strategy.name(), e));
+            }
         }
 
-        // Compare f32's which don't implement `Cmp`.
-        // https://www.reddit.com/r/rust/comments/29kia3/comment/cilrzik/?utm_source=share&utm_medium=web2x&context=3
-        if let Some(min) = unvisited
-            .iter()
-            .min_by(|a, b| a.1.dist.partial_cmp(&b.1.dist).unwrap_or(Equal))
-            .map(|(_, n)| n.pos)
-        {
-            next_pos = min;
+        let dist = route.as_ref().map(|r| r.dist);
+        let expected = first_route.as_ref().map(|r| r.dist);
+        if dist != expected {
+            problems.push(format!(
+                "{} found {:?}, but {} found {:?}",
+                strategy.name(),
+                dist,
+                first.name(),
+                expected
+            ));
         }
     }
 
-    res
+    (results, problems)
+}
+
+fn solve(input: &str, strategy: Strategy) -> Option<Route> {
+    let map = HeightMap::parse(input)
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+    strategy.shortest_route(&map)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (mut unvisited, start, end, _trails) = parse_input(input);
-    unvisited.get_mut(&start).unwrap().dist = 0.;
+    solve(input, Strategy::Bfs).map(|route| route.dist)
+}
+
+/// The shortest route from any cell of elevation 'a' to the end. Searches backwards
+/// from the end with the climbing rule inverted (at most one step *down*), so the
+/// first 'a' reached is the best trailhead.
+fn best_trailhead(map: &HeightMap) -> Option<Route> {
+    let found = bfs(
+        [map.end],
+        |pos| map.steps_back(*pos).collect::<Vec<_>>(),
+        |pos| map.height(*pos) == Some(0),
+        true,
+    )?;
+
+    let mut path = found.path.unwrap_or_default();
+    path.reverse();
+
+    Some(Route {
+        dist: found.cost as u32,
+        path,
+    })
+}
+
+/// The best trailhead, as the route from it; `start()` is the cell to begin at.
+fn solve_trailhead(input: &str) -> Option<Route> {
+    let map = HeightMap::parse(input)
+        .map_err(|e| eprintln!("{}", e))
+        .ok()?;
+
+    let route = best_trailhead(&map)?;
+    advent_of_code::debug!("best trailhead: {:?}", route.start());
+    Some(route)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    solve_trailhead(input).map(|route| route.dist)
+}
 
-    match dijkstra(&mut unvisited, start, end) {
-        Some(v) => Some(v as u32),
-        _ => None
+fn elevation(c: char) -> u8 {
+    match c {
+        'S' => 0,
+        'E' => 25,
+        c => (c as u8).saturating_sub(b'a'),
     }
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    None
+/// The route in the puzzle's notation: every step as `>`, `<`, `^` or `v`, the end
+/// as `E` and everything else as `.`.
+fn route_overlay(input: &str, route: &Route) -> Vec<Vec<char>> {
+    let mut grid: Vec<Vec<char>> = input.lines().map(|line| vec!['.'; line.len()]).collect();
+
+    for step in route.path.windows(2) {
+        let ((r0, c0), (r1, c1)) = (step[0], step[1]);
+        grid[r0][c0] = match (r1.cmp(&r0), c1.cmp(&c0)) {
+            (Ordering::Greater, _) => 'v',
+            (Ordering::Less, _) => '^',
+            (_, Ordering::Greater) => '>',
+            _ => '<',
+        };
+    }
+
+    if let Some((r, c)) = route.path.last() {
+        grid[*r][*c] = 'E';
+    }
+
+    grid
 }
 
-// pub fn part_two() -> Option<u32> {
-//     let (unvisited, _start, end, trails) = parse_input();
-//     trails
-//         .into_par_iter()
-//         .map(|start| {
-//             let mut unvisited = unvisited.clone();
-//             unvisited.get_mut(&start).unwrap().dist = 0.;
-//             dijkstra(&mut unvisited, start, end)
-//         })
-//         .map(|o| o.unwrap_or(f32::INFINITY))
-//         .min_by(|a, b| a.partial_cmp(&b).unwrap_or(Equal))
-// }
+/// Elevation letters colored from blue (a) to red (z), with the route arrows drawn on top.
+fn heatmap(input: &str, route: Option<&Route>) -> Vec<Vec<Cell>> {
+    let overlay = route.map(|route| route_overlay(input, route));
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(r, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(c, ch)| {
+                    let color = heat(elevation(ch) as f32 / 25.0);
+                    match overlay.as_ref().map(|o| o[r][c]) {
+                        Some(arrow) if arrow != '.' => Cell::colored(arrow, (255, 255, 255)),
+                        _ => Cell::colored(ch, color),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+struct Args {
+    strategy: Option<Strategy>,
+    cross_check: bool,
+    route: bool,
+    trailhead: bool,
+    heatmap: bool,
+    ppm: Option<String>,
+}
+
+impl Extras for Args {
+    const ACTION: &'static str = "run extras";
+
+    fn parse(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        Ok(Args {
+            strategy: args.opt_value_from_str("--strategy")?,
+            cross_check: args.contains("--cross-check"),
+            route: args.contains("--route"),
+            trailhead: args.contains("--trailhead"),
+            heatmap: args.contains("--heatmap"),
+            ppm: args.opt_value_from_str("--ppm")?,
+        })
+    }
+
+    /// `cargo solve 12 -- --strategy dijkstra|bfs|relaxing-dfs` solves part one with that strategy
+    /// and `--cross-check` with all of them, reporting any disagreement.
+    ///
+    /// `--route` prints the shortest route, `--trailhead` the shortest route from any 'a',
+    /// `--heatmap` prints the route over a colored elevation map and `--ppm map.ppm` saves
+    /// that map as an image.
+    fn run(self, input: &str) -> Result<(), String> {
+        if !(self.strategy.is_some()
+            || self.cross_check
+            || self.route
+            || self.trailhead
+            || self.heatmap
+            || self.ppm.is_some())
+        {
+            return Ok(());
+        }
+
+        let map = HeightMap::parse(input).map_err(|e| e.to_string())?;
+        let strategy = self.strategy.unwrap_or(Strategy::Bfs);
+
+        if self.strategy.is_some() {
+            match strategy.shortest_route(&map) {
+                Some(route) => println!("{}: {} steps", strategy.name(), route.dist),
+                None => println!("{}: no route from S to E", strategy.name()),
+            }
+        }
+
+        if self.cross_check {
+            let (results, problems) = cross_check(&map);
+
+            for (strategy, route) in results {
+                match route {
+                    Some(route) => println!("{}: {} steps", strategy.name(), route.dist),
+                    None => println!("{}: no route", strategy.name()),
+                }
+            }
+
+            if problems.is_empty() {
+                println!("All strategies agree.");
+            } else {
+                return Err(problems.join("\n"));
+            }
+        }
+
+        if self.trailhead {
+            match best_trailhead(&map) {
+                Some(route) => {
+                    let (r, c) = route.start();
+                    println!("Start at row {}, col {}: {} steps", r, c, route.dist);
+                    print!("{}", to_text(&route_overlay(input, &route)));
+                }
+                None => println!("no route from any 'a' to E"),
+            }
+        }
+
+        if !(self.route || self.heatmap || self.ppm.is_some()) {
+            return Ok(());
+        }
+
+        let route = strategy.shortest_route(&map);
+
+        if self.route {
+            match &route {
+                Some(route) => print!("{}", to_text(&route_overlay(input, route))),
+                None => println!("no route from S to E"),
+            }
+        }
+
+        let image = heatmap(input, route.as_ref());
+
+        if self.heatmap {
+            print!("{}", to_ansi(&image));
+        }
+
+        if let Some(path) = self.ppm {
+            let file = File::create(&path).map_err(|e| e.to_string())?;
+            write_ppm(&image, 4, &mut BufWriter::new(file)).map_err(|e| e.to_string())?;
+            println!("Wrote heatmap to \"{}\"", path);
+        }
+
+        Ok(())
+    }
+}
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 12);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+
+    cli::run_extras::<Args>(input);
 }
 
 #[cfg(test)]
@@ -155,6 +505,70 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 12);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(29));
+
+        // the bottom-left 'a', as in the puzzle's drawing.
+        let route = solve_trailhead(&input).unwrap();
+        assert_eq!((route.start(), route.dist), ((4, 0), 29));
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let input = advent_of_code::read_file("examples", 12);
+        let map = HeightMap::parse(&input).unwrap();
+
+        let (results, problems) = cross_check(&map);
+        assert_eq!(problems, Vec::<String>::new());
+        assert!(results
+            .iter()
+            .all(|(_, r)| r.as_ref().map(|r| r.dist) == Some(31)));
+
+        // a wide plateau of 'a's full of cycles, then a staircase up to E.
+        let plateau =
+            "Sabcdefghijklmnopqrstuvwxyz\naaaaaaaaaaaaaaaaaaaaaaaaaaE\naaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let map = HeightMap::parse(plateau).unwrap();
+        let (results, problems) = cross_check(&map);
+        assert_eq!(problems, Vec::<String>::new());
+        assert_eq!(results[2].1.as_ref().map(|r| r.dist), Some(27));
+
+        assert_eq!("relaxing-dfs".parse(), Ok(Strategy::RelaxingDfs));
+        assert!("astar".parse::<Strategy>().is_err());
+        assert_eq!(HeightMap::parse("Sa\nb!").unwrap_err().line, Some(2));
+    }
+
+    #[test]
+    fn test_route_overlay() {
+        let input = advent_of_code::read_file("examples", 12);
+        let map = HeightMap::parse(&input).unwrap();
+        let route = dijkstra(&map).unwrap();
+
+        assert_eq!(route.path.len(), 32);
+        assert_eq!(route.validate(&map, map.start, map.end), Ok(()));
+
+        let overlay = route_overlay(&input, &route);
+        let arrows = overlay
+            .iter()
+            .flatten()
+            .filter(|c| "<>^v".contains(**c))
+            .count();
+        assert_eq!(arrows, 31);
+        assert_eq!(overlay[map.end.0][map.end.1], 'E');
+
+        let image = heatmap(&input, None);
+        assert_eq!(image[0][7], Cell::colored('m', heat(12.0 / 25.0)));
+
+        let image = heatmap(&input, Some(&route));
+        assert_eq!(image[map.end.0][map.end.1].ch, 'E');
+    }
+
+    #[test]
+    fn test_best_trailhead() {
+        let input = advent_of_code::read_file("examples", 12);
+        let map = HeightMap::parse(&input).unwrap();
+
+        let route = best_trailhead(&map).unwrap();
+        assert_eq!(route.path.len(), 30);
+        assert_eq!(route.path.last(), Some(&map.end));
+        assert_eq!(route.start(), (4, 0));
     }
 }
@@ -2,3 +2,16 @@
  * Use this file if you want to extract helpers from your solutions.
  * Example import from this file: `use advent_of_code::helpers::example_fn;`.
  */
+pub mod cli;
+pub mod expr;
+pub mod graph;
+pub mod interval;
+pub mod letter_set;
+pub mod ocr;
+pub mod parse;
+pub mod search;
+pub mod top_k;
+pub mod trace;
+pub mod vfs;
+pub mod viz;
+pub mod window;
@@ -0,0 +1,102 @@
+/*
+ * Command line extras for solutions: flags after `--`, run once the answers are printed.
+ * Example: `impl cli::Extras for AnimateArgs { ... }`, then in `main`:
+ * `advent_of_code::helpers::cli::run_extras::<AnimateArgs>(input);`.
+ */
+use crate::helpers::trace;
+use pico_args::Arguments;
+use std::ffi::OsString;
+
+/// A day's own flags and what to do with them.
+pub trait Extras: Sized {
+    /// Completes "Failed to ..." when the flags can't be parsed or `run` fails.
+    const ACTION: &'static str;
+
+    /// Takes the day's flags out of `args`; the shared ones are already gone.
+    fn parse(args: &mut Arguments) -> Result<Self, pico_args::Error>;
+
+    fn run(self, input: &str) -> Result<(), String>;
+}
+
+/// Parses `args` (without the program name). The verbosity flags are shared by every
+/// day and read by `trace`, so they are skipped here; anything the day doesn't know
+/// is an error. `None` when no day flag was given at all.
+pub fn parse<E: Extras>(args: Vec<OsString>) -> Result<Option<E>, String> {
+    let args: Vec<OsString> = args
+        .into_iter()
+        .filter(|arg| arg.to_str().and_then(trace::verbosity_flag).is_none())
+        .collect();
+
+    if args.is_empty() {
+        return Ok(None);
+    }
+
+    let mut args = Arguments::from_vec(args);
+    let extras = E::parse(&mut args).map_err(|e| e.to_string())?;
+
+    let unknown = args.finish();
+    if !unknown.is_empty() {
+        return Err(format!("unknown arguments {:?}", unknown));
+    }
+
+    Ok(Some(extras))
+}
+
+/// Runs the extras requested on the command line, reporting errors on stderr.
+pub fn run_extras<E: Extras>(input: &str) {
+    let result = parse::<E>(std::env::args_os().skip(1).collect())
+        .and_then(|extras| extras.map_or(Ok(()), |extras| extras.run(input)));
+
+    if let Err(e) = result {
+        eprintln!("Failed to {}: {}", E::ACTION, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Demo {
+        loud: bool,
+        times: usize,
+    }
+
+    impl Extras for Demo {
+        const ACTION: &'static str = "run the demo";
+
+        fn parse(args: &mut Arguments) -> Result<Self, pico_args::Error> {
+            Ok(Demo {
+                loud: args.contains("--loud"),
+                times: args.opt_value_from_str("--times")?.unwrap_or(1),
+            })
+        }
+
+        fn run(self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn args(s: &str) -> Vec<OsString> {
+        s.split_whitespace().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse::<Demo>(args("")), Ok(None));
+        assert_eq!(parse::<Demo>(args("-vv --verbose")), Ok(None));
+        assert_eq!(
+            parse::<Demo>(args("--times 3 -v --loud")),
+            Ok(Some(Demo {
+                loud: true,
+                times: 3
+            }))
+        );
+
+        assert_eq!(
+            parse::<Demo>(args("--loud --quiet")),
+            Err(String::from("unknown arguments [\"--quiet\"]"))
+        );
+        assert!(parse::<Demo>(args("--times x")).is_err());
+    }
+}
@@ -0,0 +1,435 @@
+/*
+ * Arithmetic expressions with variables, e.g. the `old * old` in `new = old * old`.
+ * Example import: `use advent_of_code::helpers::expr::Expr;`, then
+ * `Expr::parse("old * 19")?.eval(&|name| (name == "old").then_some(old))`.
+ */
+use crate::helpers::parse::ParseError;
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    fn from_char(c: char) -> Option<BinOp> {
+        match c {
+            '+' => Some(BinOp::Add),
+            '-' => Some(BinOp::Sub),
+            '*' => Some(BinOp::Mul),
+            '/' => Some(BinOp::Div),
+            '%' => Some(BinOp::Rem),
+            _ => None,
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 2,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+            BinOp::Rem => '%',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(u64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+/// A syntax error at a 0-based byte offset into the expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.pos + 1, self.message)
+    }
+}
+
+impl Error for SyntaxError {}
+
+impl From<SyntaxError> for ParseError {
+    fn from(e: SyntaxError) -> Self {
+        ParseError::new(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    Overflow,
+    DivisionByZero,
+    /// Division has no general meaning in modular arithmetic.
+    ModularDivision,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "unknown variable {:?}", name),
+            EvalError::Overflow => write!(f, "arithmetic overflow"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::ModularDivision => write!(f, "division in modular arithmetic"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// Integer types expressions can be evaluated in, with overflow checks.
+pub trait Value: Copy {
+    fn from_literal(n: u64) -> Option<Self>;
+    fn neg(self) -> Option<Self>;
+    fn apply(self, op: BinOp, rhs: Self) -> Result<Self, EvalError>;
+}
+
+macro_rules! impl_value {
+    ($($t:ty),*) => {$(
+        impl Value for $t {
+            fn from_literal(n: u64) -> Option<Self> {
+                n.try_into().ok()
+            }
+
+            fn neg(self) -> Option<Self> {
+                (0 as $t).checked_sub(self)
+            }
+
+            fn apply(self, op: BinOp, rhs: Self) -> Result<Self, EvalError> {
+                if matches!(op, BinOp::Div | BinOp::Rem) && rhs == 0 {
+                    return Err(EvalError::DivisionByZero);
+                }
+
+                match op {
+                    BinOp::Add => self.checked_add(rhs),
+                    BinOp::Sub => self.checked_sub(rhs),
+                    BinOp::Mul => self.checked_mul(rhs),
+                    BinOp::Div => self.checked_div(rhs),
+                    BinOp::Rem => self.checked_rem(rhs),
+                }
+                .ok_or(EvalError::Overflow)
+            }
+        }
+    )*};
+}
+
+impl_value!(u64, i64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Num(u64),
+    Ident(String),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, SyntaxError> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if BinOp::from_char(c).is_some() => Token::Op(c),
+            c if c.is_ascii_digit() || c.is_alphabetic() || c == '_' => {
+                let mut end = pos + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    end = i + c.len_utf8();
+                }
+
+                let word = &text[pos..end];
+                if c.is_ascii_digit() {
+                    Token::Num(word.parse().map_err(|_| SyntaxError {
+                        pos,
+                        message: format!("invalid number {:?}", word),
+                    })?)
+                } else {
+                    Token::Ident(String::from(word))
+                }
+            }
+            c => {
+                return Err(SyntaxError {
+                    pos,
+                    message: format!("unexpected character {:?}", c),
+                })
+            }
+        };
+
+        tokens.push((pos, token));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    // position reported for errors at the end of the input.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn pos(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(pos, _)| *pos)
+    }
+
+    fn error(&self, message: &str) -> SyntaxError {
+        let found = match self.peek() {
+            Some(Token::Num(n)) => format!("{}", n),
+            Some(Token::Ident(name)) => format!("{:?}", name),
+            Some(Token::Op(c)) => format!("{:?}", c),
+            Some(Token::Open) => String::from("'('"),
+            Some(Token::Close) => String::from("')'"),
+            None => String::from("end of input"),
+        };
+
+        SyntaxError {
+            pos: self.pos(),
+            message: format!("expected {}, found {}", message, found),
+        }
+    }
+
+    /// Precedence climbing: parses operators binding at least as tight as `min`.
+    fn binary(&mut self, min: u8) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.unary()?;
+
+        while let Some(Token::Op(c)) = self.peek() {
+            let op = BinOp::from_char(*c).unwrap();
+            if op.precedence() < min {
+                break;
+            }
+
+            self.next += 1;
+            let rhs = self.binary(op.precedence() + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = match self.peek() {
+            Some(Token::Op('-')) => {
+                self.next += 1;
+                return Ok(Expr::Neg(Box::new(self.unary()?)));
+            }
+            Some(Token::Num(n)) => Expr::Num(*n),
+            Some(Token::Ident(name)) => Expr::Var(name.clone()),
+            Some(Token::Open) => {
+                self.next += 1;
+                let inner = self.binary(0)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error("')'"));
+                }
+                inner
+            }
+            _ => return Err(self.error("a number, variable or '('")),
+        };
+
+        self.next += 1;
+        Ok(expr)
+    }
+}
+
+impl Expr {
+    /// `+ - * / %` with the usual precedence, left associative, unary minus and parentheses.
+    pub fn parse(text: &str) -> Result<Expr, SyntaxError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            next: 0,
+            end: text.len(),
+        };
+
+        let expr = parser.binary(0)?;
+        if parser.peek().is_some() {
+            return Err(parser.error("an operator"));
+        }
+
+        Ok(expr)
+    }
+
+    /// Names of all variables, in order of first use.
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a>(expr: &'a Expr, out: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Num(_) => {}
+                Expr::Var(name) => {
+                    if !out.contains(&name.as_str()) {
+                        out.push(name);
+                    }
+                }
+                Expr::Neg(inner) => collect(inner, out),
+                Expr::Binary(_, lhs, rhs) => {
+                    collect(lhs, out);
+                    collect(rhs, out);
+                }
+            }
+        }
+
+        let mut out = vec![];
+        collect(self, &mut out);
+        out
+    }
+
+    /// Evaluates with checked arithmetic; `vars` looks up variable values.
+    pub fn eval<T: Value>(&self, vars: &impl Fn(&str) -> Option<T>) -> Result<T, EvalError> {
+        match self {
+            Expr::Num(n) => T::from_literal(*n).ok_or(EvalError::Overflow),
+            Expr::Var(name) => vars(name).ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Neg(inner) => inner.eval(vars)?.neg().ok_or(EvalError::Overflow),
+            Expr::Binary(op, lhs, rhs) => lhs.eval(vars)?.apply(*op, rhs.eval(vars)?),
+        }
+    }
+
+    /// Evaluates modulo `modulus`, which never overflows. The result is in `0..modulus`.
+    pub fn eval_mod(
+        &self,
+        vars: &impl Fn(&str) -> Option<u64>,
+        modulus: u64,
+    ) -> Result<u64, EvalError> {
+        if modulus == 0 {
+            return Err(EvalError::DivisionByZero);
+        }
+
+        let m = modulus as u128;
+        let value = match self {
+            Expr::Num(n) => *n as u128 % m,
+            Expr::Var(name) => {
+                vars(name).ok_or_else(|| EvalError::UnknownVariable(name.clone()))? as u128 % m
+            }
+            Expr::Neg(inner) => (m - inner.eval_mod(vars, modulus)? as u128) % m,
+            Expr::Binary(op, lhs, rhs) => {
+                let a = lhs.eval_mod(vars, modulus)? as u128;
+                let b = rhs.eval_mod(vars, modulus)? as u128;
+
+                match op {
+                    BinOp::Add => (a + b) % m,
+                    BinOp::Sub => (a + m - b) % m,
+                    BinOp::Mul => a * b % m,
+                    BinOp::Div | BinOp::Rem => return Err(EvalError::ModularDivision),
+                }
+            }
+        };
+
+        Ok(value as u64)
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Expr::parse(s)?)
+    }
+}
+
+/// Prints with only the parentheses the precedence rules require.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn operand(f: &mut fmt::Formatter<'_>, expr: &Expr, min: u8) -> fmt::Result {
+            match expr {
+                Expr::Binary(op, _, _) if op.precedence() < min => write!(f, "({})", expr),
+                _ => write!(f, "{}", expr),
+            }
+        }
+
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Neg(inner) => {
+                write!(f, "-")?;
+                operand(f, inner, u8::MAX)
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                operand(f, lhs, op.precedence())?;
+                write!(f, " {} ", op.symbol())?;
+                operand(f, rhs, op.precedence() + 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(text: &str, old: i64) -> Result<i64, EvalError> {
+        Expr::parse(text)
+            .unwrap()
+            .eval(&|name| (name == "old").then_some(old))
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval("old * old", 7), Ok(49));
+        assert_eq!(eval("old + 6 * 2", 1), Ok(13));
+        assert_eq!(eval("(old + 6) * 2", 1), Ok(14));
+        assert_eq!(eval("10 - 4 - 3", 0), Ok(3));
+        assert_eq!(eval("-old % 4", 7), Ok(-3));
+        assert_eq!(eval("old / 0", 7), Err(EvalError::DivisionByZero));
+        assert_eq!(
+            eval("x + 1", 7),
+            Err(EvalError::UnknownVariable(String::from("x")))
+        );
+
+        let square = Expr::parse("old * old").unwrap();
+        assert_eq!(
+            square.eval::<u64>(&|_| Some(u64::MAX)),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(square.eval_mod(&|_| Some(u64::MAX), 96577), Ok(69853));
+        assert_eq!(
+            Expr::parse("1 - old").unwrap().eval_mod(&|_| Some(3), 10),
+            Ok(8)
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = Expr::parse("old * (3 + )").unwrap_err();
+        assert_eq!(err.pos, 11);
+        assert_eq!(
+            err.to_string(),
+            "column 12: expected a number, variable or '(', found ')'"
+        );
+
+        assert_eq!(Expr::parse("(1 + 2").unwrap_err().pos, 6);
+        assert_eq!(Expr::parse("old old").unwrap_err().pos, 4);
+        assert_eq!(Expr::parse("old ^ 2").unwrap_err().pos, 4);
+        assert_eq!(Expr::parse("").unwrap_err().pos, 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let expr: Expr = "(a + b) * (c - (d - e)) - -f".parse().unwrap();
+        assert_eq!(expr.to_string(), "(a + b) * (c - (d - e)) - -f");
+        assert_eq!(expr.variables(), vec!["a", "b", "c", "d", "e", "f"]);
+        assert_eq!(Expr::parse("1 + 2 * 3").unwrap().to_string(), "1 + 2 * 3");
+    }
+}
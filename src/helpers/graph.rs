@@ -0,0 +1,189 @@
+/*
+ * Adapters from puzzle data to `petgraph` graphs.
+ * Example import: `use advent_of_code::helpers::graph::{from_grid, KeyedGraph};`.
+ */
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::{collections::HashMap, hash::Hash};
+
+/// Grid coordinate as `(row, col)`.
+pub type Coord = (usize, usize);
+
+/// A directed graph plus the mapping between puzzle keys and node indices.
+#[derive(Debug, Clone)]
+pub struct KeyedGraph<K, N, E> {
+    pub graph: DiGraph<N, E>,
+    pub index: HashMap<K, NodeIndex>,
+    /// `keys[node.index()]` is the key a node was created for.
+    pub keys: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash, N, E> KeyedGraph<K, N, E> {
+    pub fn new() -> Self {
+        KeyedGraph {
+            graph: DiGraph::new(),
+            index: HashMap::new(),
+            keys: vec![],
+        }
+    }
+
+    /// Adds a node for `key`, or returns the existing one (its weight is kept).
+    pub fn add_node(&mut self, key: K, weight: N) -> NodeIndex {
+        if let Some(node) = self.index.get(&key) {
+            return *node;
+        }
+
+        let node = self.graph.add_node(weight);
+        self.index.insert(key.clone(), node);
+        self.keys.push(key);
+        node
+    }
+
+    pub fn node(&self, key: &K) -> Option<NodeIndex> {
+        self.index.get(key).copied()
+    }
+
+    pub fn key(&self, node: NodeIndex) -> &K {
+        &self.keys[node.index()]
+    }
+}
+
+impl<K: Clone + Eq + Hash, N, E> Default for KeyedGraph<K, N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a graph with one node per cell, weighted by the cell value.
+/// `edge(from, to)` decides whether there is an edge between orthogonal neighbours,
+/// e.g. `|a, b| *b <= *a + 1` for "climb at most one".
+pub fn from_grid<T, F>(grid: &[Vec<T>], mut edge: F) -> KeyedGraph<Coord, T, ()>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut g = KeyedGraph::new();
+
+    for (row, line) in grid.iter().enumerate() {
+        for (col, cell) in line.iter().enumerate() {
+            g.add_node((row, col), cell.clone());
+        }
+    }
+
+    for (row, line) in grid.iter().enumerate() {
+        for (col, cell) in line.iter().enumerate() {
+            let from = g.index[&(row, col)];
+
+            for (r, c) in neighbours(grid, row, col) {
+                if edge(cell, &grid[r][c]) {
+                    let to = g.index[&(r, c)];
+                    g.graph.add_edge(from, to, ());
+                }
+            }
+        }
+    }
+
+    g
+}
+
+fn neighbours<T>(grid: &[Vec<T>], row: usize, col: usize) -> Vec<Coord> {
+    let mut out = vec![];
+
+    if row > 0 && col < grid[row - 1].len() {
+        out.push((row - 1, col));
+    }
+    if row + 1 < grid.len() && col < grid[row + 1].len() {
+        out.push((row + 1, col));
+    }
+    if col > 0 {
+        out.push((row, col - 1));
+    }
+    if col + 1 < grid[row].len() {
+        out.push((row, col + 1));
+    }
+
+    out
+}
+
+/// Builds a graph from `(from, to, weight)` edges; nodes are created on first sight
+/// and weighted by their key.
+pub fn from_edges<K, E>(edges: impl IntoIterator<Item = (K, K, E)>) -> KeyedGraph<K, K, E>
+where
+    K: Clone + Eq + Hash,
+{
+    let mut g = KeyedGraph::new();
+
+    for (from, to, weight) in edges {
+        let a = g.add_node(from.clone(), from);
+        let b = g.add_node(to.clone(), to);
+        g.graph.add_edge(a, b, weight);
+    }
+
+    g
+}
+
+/// Builds an unweighted graph from `node -> [neighbours]` entries.
+/// Nodes without outgoing edges still get a node.
+pub fn from_adjacency<K, I>(list: impl IntoIterator<Item = (K, I)>) -> KeyedGraph<K, K, ()>
+where
+    K: Clone + Eq + Hash,
+    I: IntoIterator<Item = K>,
+{
+    let mut g = KeyedGraph::new();
+
+    for (from, targets) in list {
+        let a = g.add_node(from.clone(), from);
+
+        for to in targets {
+            let b = g.add_node(to.clone(), to);
+            g.graph.add_edge(a, b, ());
+        }
+    }
+
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::algo::{dijkstra, kosaraju_scc, toposort};
+    use petgraph::dot::{Config, Dot};
+
+    #[test]
+    fn test_from_grid() {
+        let grid: Vec<Vec<u8>> = ["abc", "abd", "zzc"]
+            .iter()
+            .map(|l| l.bytes().collect())
+            .collect();
+
+        let g = from_grid(&grid, |a, b| *b <= *a + 1);
+        assert_eq!(g.graph.node_count(), 9);
+
+        let start = g.node(&(0, 0)).unwrap();
+        let dist = dijkstra(&g.graph, start, None, |_| 1);
+        assert_eq!(dist[&g.node(&(1, 2)).unwrap()], 3);
+        assert!(!dist.contains_key(&g.node(&(2, 0)).unwrap()));
+        assert_eq!(g.key(start), &(0, 0));
+    }
+
+    #[test]
+    fn test_from_adjacency() {
+        let g = from_adjacency([("a", vec!["b", "c"]), ("b", vec!["c"]), ("c", vec![])]);
+
+        let order: Vec<&str> = toposort(&g.graph, None)
+            .unwrap()
+            .into_iter()
+            .map(|n| *g.key(n))
+            .collect();
+        assert_eq!(order, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let g = from_edges([(1, 2, 5), (2, 1, 5), (2, 3, 1)]);
+
+        assert_eq!(kosaraju_scc(&g.graph).len(), 2);
+
+        let dot = format!("{:?}", Dot::with_config(&g.graph, &[Config::EdgeNoLabel]));
+        assert!(dot.starts_with("digraph {"));
+    }
+}
@@ -0,0 +1,282 @@
+/*
+ * Inclusive integer intervals and sets of disjoint intervals.
+ * Example import: `use advent_of_code::helpers::interval::{Interval, RangeSet};`.
+ */
+use crate::helpers::parse::ParseError;
+use std::{fmt, str::FromStr};
+
+/// Inclusive range `start..=end`. Always non-empty: `start <= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// Panics if `start > end`.
+    pub fn new(start: i64, end: i64) -> Self {
+        assert!(start <= end, "empty interval {}-{}", start, end);
+        Interval { start, end }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.start.abs_diff(self.end) + 1
+    }
+
+    /// Always false, an interval holds at least one value.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.start <= x && x <= self.end
+    }
+
+    /// Whether `other` lies completely inside `self`.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Overlapping or directly next to each other, e.g. `2-4` and `5-7`.
+    pub fn touches(&self, other: &Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// The merged interval, if the two touch; otherwise the union is not an interval.
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        self.touches(other)
+            .then(|| Interval::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// The parts of `self` not covered by `other` (zero, one or two intervals).
+    pub fn difference(&self, other: &Interval) -> Vec<Interval> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut out = vec![];
+        if self.start < other.start {
+            out.push(Interval::new(self.start, other.start - 1));
+        }
+        if other.end < self.end {
+            out.push(Interval::new(other.end + 1, self.end));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ParseError;
+
+    /// Parses `"2-4"`; a leading `-` on either bound is a sign: `"-3--1"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::new(format!("invalid interval {:?}", s));
+
+        let split = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(i, _)| i)
+            .ok_or_else(invalid)?;
+
+        let start: i64 = s[..split].trim().parse().map_err(|_| invalid())?;
+        let end: i64 = s[split + 1..].trim().parse().map_err(|_| invalid())?;
+
+        if start > end {
+            return Err(invalid());
+        }
+
+        Ok(Interval::new(start, end))
+    }
+}
+
+/// A set of integers stored as sorted, disjoint, non-touching intervals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet { intervals: vec![] }
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers covered, counting overlaps once.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let mut merged = interval;
+        let mut out = Vec::with_capacity(self.intervals.len() + 1);
+        let mut placed = false;
+
+        for i in self.intervals.drain(..) {
+            if let Some(u) = merged.union(&i) {
+                merged = u;
+            } else if i.end < merged.start {
+                out.push(i);
+            } else {
+                if !placed {
+                    out.push(merged);
+                    placed = true;
+                }
+                out.push(i);
+            }
+        }
+
+        if !placed {
+            out.push(merged);
+        }
+
+        self.intervals = out;
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|i| i.difference(&interval))
+            .collect();
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.intervals.partition_point(|i| i.end < x);
+        self.intervals.get(i).is_some_and(|i| i.contains(x))
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut out = self.clone();
+        for i in &other.intervals {
+            out.insert(*i);
+        }
+        out
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let (mut a, mut b) = (0, 0);
+        let mut out = RangeSet::new();
+
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (self.intervals[a], other.intervals[b]);
+
+            if let Some(i) = x.intersection(&y) {
+                out.intervals.push(i);
+            }
+
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        out
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut out = self.clone();
+        for i in &other.intervals {
+            out.remove(*i);
+        }
+        out
+    }
+
+    /// The uncovered parts of `within`.
+    pub fn gaps(&self, within: Interval) -> Vec<Interval> {
+        let mut window = RangeSet::new();
+        window.insert(within);
+        window.difference(self).intervals
+    }
+}
+
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for i in iter {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iv(s: &str) -> Interval {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(iv("2-8"), Interval::new(2, 8));
+        assert_eq!(iv("-3--1"), Interval::new(-3, -1));
+        assert!("2".parse::<Interval>().is_err());
+        assert!("8-2".parse::<Interval>().is_err());
+        assert!("a-2".parse::<Interval>().is_err());
+
+        assert!(iv("2-8").contains_interval(&iv("3-7")));
+        assert!(!iv("3-7").contains_interval(&iv("2-8")));
+        assert!(iv("5-7").overlaps(&iv("7-9")));
+        assert!(!iv("2-3").overlaps(&iv("4-5")));
+
+        assert_eq!(iv("2-6").intersection(&iv("4-8")), Some(iv("4-6")));
+        assert_eq!(iv("2-3").union(&iv("4-5")), Some(iv("2-5")));
+        assert_eq!(iv("2-3").union(&iv("5-6")), None);
+        assert_eq!(iv("2-8").difference(&iv("4-5")), vec![iv("2-3"), iv("6-8")]);
+        assert_eq!(iv("4-6").len(), 3);
+    }
+
+    #[test]
+    fn test_range_set() {
+        let set: RangeSet = ["2-4", "6-8", "3-5", "12-14"].into_iter().map(iv).collect();
+
+        assert_eq!(set.intervals(), &[iv("2-8"), iv("12-14")]);
+        assert_eq!(set.len(), 10);
+        assert!(set.contains(13));
+        assert!(!set.contains(10));
+        assert_eq!(
+            set.gaps(iv("0-15")),
+            vec![iv("0-1"), iv("9-11"), iv("15-15")]
+        );
+
+        let other: RangeSet = ["4-12"].into_iter().map(iv).collect();
+        assert_eq!(
+            set.intersection(&other).intervals(),
+            &[iv("4-8"), iv("12-12")]
+        );
+        assert_eq!(
+            set.difference(&other).intervals(),
+            &[iv("2-3"), iv("13-14")]
+        );
+        assert_eq!(set.union(&other).intervals(), &[iv("2-14")]);
+    }
+}
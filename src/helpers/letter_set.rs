@@ -0,0 +1,198 @@
+/*
+ * Allocation-free sets of up to 64 symbols, e.g. rucksack item types.
+ * Example import: `use advent_of_code::helpers::letter_set::{Alphabet, LetterSet};`.
+ */
+use crate::helpers::parse::ParseError;
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+const UNKNOWN: u8 = u8::MAX;
+
+/// Maps ASCII symbols to bit positions `0..64` and priorities `first_priority + position`.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    lookup: [u8; 128],
+    symbols: Vec<char>,
+    first_priority: u32,
+}
+
+impl Alphabet {
+    /// Panics on more than 64 symbols, non-ASCII or duplicate symbols.
+    pub fn new(symbols: &str, first_priority: u32) -> Self {
+        let symbols: Vec<char> = symbols.chars().collect();
+        assert!(symbols.len() <= 64, "an alphabet holds at most 64 symbols");
+
+        let mut lookup = [UNKNOWN; 128];
+        for (i, c) in symbols.iter().enumerate() {
+            assert!(c.is_ascii(), "non-ASCII symbol {:?}", c);
+            assert!(lookup[*c as usize] == UNKNOWN, "duplicate symbol {:?}", c);
+            lookup[*c as usize] = i as u8;
+        }
+
+        Alphabet {
+            lookup,
+            symbols,
+            first_priority,
+        }
+    }
+
+    /// `a-z` have priorities 1 through 26, `A-Z` 27 through 52.
+    pub fn rucksack() -> Self {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ", 1)
+    }
+
+    pub fn index(&self, c: char) -> Result<u32, ParseError> {
+        match self.lookup.get(c as usize) {
+            Some(i) if *i != UNKNOWN => Ok(*i as u32),
+            _ => Err(ParseError::new(format!("unknown symbol {:?}", c))),
+        }
+    }
+
+    pub fn priority(&self, c: char) -> Result<u32, ParseError> {
+        self.index(c).map(|i| self.first_priority + i)
+    }
+
+    /// Priority of the symbol at bit position `index`.
+    pub fn priority_of(&self, index: u32) -> u32 {
+        self.first_priority + index
+    }
+
+    pub fn symbol(&self, index: u32) -> char {
+        self.symbols[index as usize]
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LetterSet(pub u64);
+
+impl LetterSet {
+    pub const EMPTY: LetterSet = LetterSet(0);
+
+    pub fn parse(s: &str, alphabet: &Alphabet) -> Result<Self, ParseError> {
+        let mut set = LetterSet::EMPTY;
+        for c in s.chars() {
+            set.insert(alphabet.index(c)?);
+        }
+        Ok(set)
+    }
+
+    pub fn insert(&mut self, index: u32) {
+        self.0 |= 1 << index;
+    }
+
+    pub fn contains(&self, index: u32) -> bool {
+        self.0 & (1 << index) != 0
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Intersection of all sets; the empty set for no sets.
+    pub fn intersect_all(sets: impl IntoIterator<Item = LetterSet>) -> LetterSet {
+        let mut iter = sets.into_iter();
+        let first = iter.next().unwrap_or(LetterSet::EMPTY);
+        iter.fold(first, |acc, s| acc & s)
+    }
+
+    pub fn union_all(sets: impl IntoIterator<Item = LetterSet>) -> LetterSet {
+        sets.into_iter().fold(LetterSet::EMPTY, |acc, s| acc | s)
+    }
+
+    /// Bit positions in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            (bits != 0).then(|| {
+                let i = bits.trailing_zeros();
+                bits &= bits - 1;
+                i
+            })
+        })
+    }
+
+    pub fn symbols<'a>(&self, alphabet: &'a Alphabet) -> impl Iterator<Item = char> + 'a {
+        self.iter().map(|i| alphabet.symbol(i))
+    }
+
+    pub fn priority_sum(&self, alphabet: &Alphabet) -> u32 {
+        self.iter().map(|i| alphabet.priority_of(i)).sum()
+    }
+}
+
+impl BitAnd for LetterSet {
+    type Output = LetterSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        LetterSet(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for LetterSet {
+    type Output = LetterSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        LetterSet(self.0 | rhs.0)
+    }
+}
+
+impl Sub for LetterSet {
+    type Output = LetterSet;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        LetterSet(self.0 & !rhs.0)
+    }
+}
+
+impl fmt::Debug for LetterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabet() {
+        let a = Alphabet::rucksack();
+
+        assert_eq!(a.priority('p'), Ok(16));
+        assert_eq!(a.priority('L'), Ok(38));
+        assert!(a.priority('1').is_err());
+        assert!(a.priority('é').is_err());
+
+        let digits = Alphabet::new("0123456789", 0);
+        assert_eq!(digits.priority('7'), Ok(7));
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = Alphabet::rucksack();
+        let set = |s| LetterSet::parse(s, &a).unwrap();
+
+        let common = set("vJrwpWtwJgWr") & set("hcsFMMfFFhFp");
+        assert_eq!(common.symbols(&a).collect::<String>(), "p");
+        assert_eq!(common.priority_sum(&a), 16);
+
+        let badge = LetterSet::intersect_all([
+            set("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            set("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"),
+            set("PmmdzqPrVvPwwTWBwg"),
+        ]);
+        assert_eq!(badge.symbols(&a).collect::<String>(), "r");
+
+        assert_eq!(LetterSet::union_all([set("ab"), set("bc")]), set("abc"));
+        assert_eq!(set("abc") - set("b"), set("ac"));
+        assert_eq!(set("aab").len(), 2);
+        assert!(LetterSet::intersect_all([]).is_empty());
+        assert!(LetterSet::parse("ab1", &a).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * Reads the block letters some puzzles draw with `#` and `.`, in the 4x6 and 6x10 fonts.
+ * Example import: `use advent_of_code::helpers::ocr;`, then `ocr::read(&screen)`.
+ */
+use crate::helpers::parse::ParseError;
+
+pub struct Font {
+    pub height: usize,
+    pub glyphs: &'static [(char, &'static str)],
+}
+
+pub const SMALL: Font = Font {
+    height: 6,
+    glyphs: &[
+        ('A', ".##.|#..#|#..#|####|#..#|#..#"),
+        ('B', "###.|#..#|###.|#..#|#..#|###."),
+        ('C', ".##.|#..#|#...|#...|#..#|.##."),
+        ('E', "####|#...|###.|#...|#...|####"),
+        ('F', "####|#...|###.|#...|#...|#..."),
+        ('G', ".##.|#..#|#...|#.##|#..#|.###"),
+        ('H', "#..#|#..#|####|#..#|#..#|#..#"),
+        ('I', ".###|..#.|..#.|..#.|..#.|.###"),
+        ('J', "..##|...#|...#|...#|#..#|.##."),
+        ('K', "#..#|#.#.|##..|#.#.|#.#.|#..#"),
+        ('L', "#...|#...|#...|#...|#...|####"),
+        ('O', ".##.|#..#|#..#|#..#|#..#|.##."),
+        ('P', "###.|#..#|#..#|###.|#...|#..."),
+        ('R', "###.|#..#|#..#|###.|#.#.|#..#"),
+        ('S', ".###|#...|#...|.##.|...#|###."),
+        ('U', "#..#|#..#|#..#|#..#|#..#|.##."),
+        // the one glyph that needs a fifth column.
+        ('Y', "#...#|#...#|.#.#.|..#..|..#..|..#.."),
+        ('Z', "####|...#|..#.|.#..|#...|####"),
+    ],
+};
+
+#[rustfmt::skip]
+pub const LARGE: Font = Font {
+    height: 10,
+    glyphs: &[
+        ('A', "..##..|.#..#.|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#"),
+        ('B', "#####.|#....#|#....#|#....#|#####.|#....#|#....#|#....#|#....#|#####."),
+        ('C', ".####.|#....#|#.....|#.....|#.....|#.....|#.....|#.....|#....#|.####."),
+        ('E', "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|######"),
+        ('F', "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|#....."),
+        ('G', ".####.|#....#|#.....|#.....|#.....|#..###|#....#|#....#|#...##|.###.#"),
+        ('H', "#....#|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#|#....#"),
+        ('J', "...###|....#.|....#.|....#.|....#.|....#.|....#.|#...#.|#...#.|.###.."),
+        ('K', "#....#|#...#.|#..#..|#.#...|##....|##....|#.#...|#..#..|#...#.|#....#"),
+        ('L', "#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|######"),
+        ('N', "#....#|##...#|##...#|#.#..#|#.#..#|#..#.#|#..#.#|#...##|#...##|#....#"),
+        ('P', "#####.|#....#|#....#|#....#|#####.|#.....|#.....|#.....|#.....|#....."),
+        ('R', "#####.|#....#|#....#|#....#|#####.|#..#..|#...#.|#...#.|#....#|#....#"),
+        ('X', "#....#|#....#|.#..#.|.#..#.|..##..|..##..|.#..#.|.#..#.|#....#|#....#"),
+        ('Z', "######|.....#|.....#|....#.|...#..|..#...|.#....|#.....|#.....|######"),
+    ],
+};
+
+pub const FONTS: [&Font; 2] = [&SMALL, &LARGE];
+
+/// Parses a `#`/`.` drawing into a grid of lit pixels; spaces count as unlit and blank
+/// lines are skipped, so a picture printed after a newline can be passed as is.
+pub fn parse_image(text: &str) -> Vec<Vec<bool>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .collect()
+}
+
+pub fn read(text: &str) -> Result<String, ParseError> {
+    read_grid(&parse_image(text))
+}
+
+/// Splits the image on unlit columns and looks up every glyph in the font matching
+/// the image height.
+pub fn read_grid(grid: &[Vec<bool>]) -> Result<String, ParseError> {
+    let font = FONTS
+        .iter()
+        .find(|font| font.height == grid.len())
+        .ok_or_else(|| ParseError::new(format!("no font is {} pixels high", grid.len())))?;
+
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize, y: usize| grid[y].get(x).copied().unwrap_or(false);
+    let column_lit = |x: usize| (0..grid.len()).any(|y| lit(x, y));
+
+    let mut out = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if !column_lit(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && column_lit(x) {
+            x += 1;
+        }
+
+        let rows: Vec<String> = (0..grid.len())
+            .map(|y| {
+                (start..x)
+                    .map(|x| if lit(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        match lookup(font, &rows) {
+            Some(c) => out.push(c),
+            None => {
+                return Err(ParseError::new(format!(
+                    "unknown glyph at column {}:\n{}",
+                    start + 1,
+                    rows.join("\n")
+                )))
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn lookup(font: &Font, rows: &[String]) -> Option<char> {
+    font.glyphs
+        .iter()
+        .find(|(_, glyph)| trim_columns(glyph) == rows)
+        .map(|(c, _)| *c)
+}
+
+/// Glyph rows without the unlit columns at either side, as segmentation cuts them off.
+fn trim_columns(glyph: &str) -> Vec<String> {
+    let rows: Vec<&str> = glyph.split('|').collect();
+    let lit = |x: usize| rows.iter().any(|row| row.as_bytes()[x] == b'#');
+
+    let width = rows[0].len();
+    let start = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let end = (0..width).rev().find(|x| lit(*x)).map_or(0, |x| x + 1);
+
+    rows.iter()
+        .map(|row| String::from(&row[start..end]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_font() {
+        let screen = "
+###..#....####.####.#..#.###...##..#..#.
+#..#.#.......#.#....#..#.#..#.#..#.#..#.
+#..#.#......#..###..####.#..#.#....####.
+###..#.....#...#....#..#.###..#....#..#.
+#....#....#....#....#..#.#....#..#.#..#.
+#....####.####.####.#..#.#.....##..#..#.";
+        assert_eq!(read(screen), Ok(String::from("PLZEHPCH")));
+
+        let grid: Vec<Vec<bool>> = [
+            ".##..###", "#..#..#.", "#.....#.", "#.##..#.", "#..#..#.", ".###.###",
+        ]
+        .iter()
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect();
+        assert_eq!(read_grid(&grid), Ok(String::from("GI")));
+    }
+
+    /// Every glyph of `font` side by side, two unlit columns apart.
+    fn draw_all(font: &Font) -> String {
+        font.glyphs
+            .iter()
+            .fold(vec![String::new(); font.height], |mut rows, (_, glyph)| {
+                for (row, line) in rows.iter_mut().zip(glyph.split('|')) {
+                    row.push_str(line);
+                    row.push_str("..");
+                }
+                rows
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn test_font_tables() {
+        assert_eq!(
+            read(&draw_all(&SMALL)),
+            Ok(String::from("ABCEFGHIJKLOPRSUYZ"))
+        );
+        assert_eq!(read(&draw_all(&LARGE)), Ok(String::from("ABCEFGHJKLNPRXZ")));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let err = read("#..#.#\n#..#.#\n####.#\n#..#.#\n#..#.#\n#..#.#").unwrap_err();
+        assert!(err.message.starts_with("unknown glyph at column 6"));
+
+        assert!(read("#\n#").is_err());
+    }
+}
@@ -0,0 +1,347 @@
+/*
+ * Parsers for common puzzle input shapes.
+ * Example import: `use advent_of_code::helpers::parse::{paragraphs, Template};`.
+ */
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number in the input, if known.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ParseError {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the line number unless a more precise one is already known.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn parse_value<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    s.parse()
+        .map_err(|_| ParseError::new(format!("could not parse {:?}", s)))
+}
+
+/// Applies `f` to every line, tagging errors with their line number.
+pub fn parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+/// A group of consecutive non-blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paragraph<'a> {
+    /// 1-based line number of the first line.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Paragraph<'a> {
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        self.text.lines()
+    }
+
+    /// Like `parse_lines`, with line numbers relative to the whole input.
+    pub fn parse_lines<T>(
+        &self,
+        f: impl FnMut(&str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        parse_lines(self.text, f).map_err(|mut e| {
+            e.line = e.line.map(|l| l + self.line - 1);
+            e
+        })
+    }
+}
+
+/// Splits input on blank lines. Runs of blank lines count as one separator.
+pub fn paragraphs(input: &str) -> Vec<Paragraph<'_>> {
+    let mut out = vec![];
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split('\n').enumerate() {
+        let end = offset + line.len();
+
+        if line.trim().is_empty() {
+            if let Some((line, from)) = start.take() {
+                out.push(Paragraph {
+                    line,
+                    text: input[from..offset].trim_end_matches(['\n', '\r']),
+                });
+            }
+        } else if start.is_none() {
+            start = Some((i + 1, offset));
+        }
+
+        offset = end + 1;
+    }
+
+    if let Some((line, from)) = start {
+        out.push(Paragraph {
+            line,
+            text: input[from..].trim_end_matches(['\n', '\r']),
+        });
+    }
+
+    out
+}
+
+/// Extracts every signed integer from arbitrary text: `"x=-3, y=12"` -> `[-3, 12]`.
+pub fn ints(text: &str) -> Vec<i64> {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if negative || bytes[i].is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            if let Ok(n) = text[start..i].parse() {
+                out.push(n);
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    out
+}
+
+/// Extracts every unsigned integer; `-` is treated as a separator: `"2-4"` -> `[2, 4]`.
+pub fn uints(text: &str) -> Vec<u64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// Parses a `delim`-separated list, trimming whitespace around items: `"79, 98"`.
+pub fn list<T: FromStr>(text: &str, delim: &str) -> Result<Vec<T>, ParseError> {
+    text.split(delim)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_value)
+        .collect()
+}
+
+/// Parses `key<sep>value` lines, trimming both sides. Blank lines are skipped.
+pub fn key_values<'a>(input: &'a str, sep: &str) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    let mut out = vec![];
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match line.split_once(sep) {
+            Some((k, v)) => out.push((k.trim(), v.trim())),
+            None => {
+                return Err(
+                    ParseError::new(format!("missing {:?} in {:?}", sep, line)).at_line(i + 1)
+                )
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// A line pattern with `{}` placeholders, e.g. `"move {} from {} to {}"`.
+#[derive(Debug, Clone)]
+pub struct Template {
+    pattern: String,
+    literals: Vec<String>,
+}
+
+impl Template {
+    /// Panics if two placeholders are adjacent, since the split between them is ambiguous.
+    pub fn new(pattern: &str) -> Self {
+        let literals: Vec<String> = pattern.split("{}").map(String::from).collect();
+
+        assert!(
+            literals
+                .iter()
+                .skip(1)
+                .take(literals.len().saturating_sub(2))
+                .all(|l| !l.is_empty()),
+            "adjacent placeholders in template {:?}",
+            pattern
+        );
+
+        Template {
+            pattern: String::from(pattern),
+            literals,
+        }
+    }
+
+    /// Returns the raw text of every placeholder.
+    pub fn captures<'a>(&self, text: &'a str) -> Result<Vec<&'a str>, ParseError> {
+        let mismatch = || ParseError::new(format!("{:?} does not match {:?}", text, self.pattern));
+
+        let mut rest = text
+            .strip_prefix(self.literals[0].as_str())
+            .ok_or_else(mismatch)?;
+        let mut out = vec![];
+
+        for (i, literal) in self.literals.iter().enumerate().skip(1) {
+            let is_last = i == self.literals.len() - 1;
+
+            let end = if is_last {
+                if literal.is_empty() {
+                    rest.len()
+                } else {
+                    rest.rfind(literal.as_str()).ok_or_else(mismatch)?
+                }
+            } else {
+                rest.find(literal.as_str()).ok_or_else(mismatch)?
+            };
+
+            out.push(&rest[..end]);
+            rest = &rest[end + literal.len()..];
+        }
+
+        if !rest.is_empty() {
+            return Err(mismatch());
+        }
+
+        Ok(out)
+    }
+
+    /// Parses the placeholders into a tuple: `let (n, a, b): (usize, usize, usize) = t.parse(line)?`.
+    pub fn parse<T: FromCaptures>(&self, text: &str) -> Result<T, ParseError> {
+        T::from_captures(&self.captures(text)?)
+    }
+
+    /// Parses every line of `input`, tagging errors with their line number.
+    pub fn parse_lines<T: FromCaptures>(&self, input: &str) -> Result<Vec<T>, ParseError> {
+        parse_lines(input, |line| self.parse(line))
+    }
+}
+
+/// Conversion from template captures; implemented for tuples of `FromStr` types.
+pub trait FromCaptures: Sized {
+    fn from_captures(captures: &[&str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($n:expr; $($t:ident $i:tt),+) => {
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+) {
+            fn from_captures(captures: &[&str]) -> Result<Self, ParseError> {
+                if captures.len() != $n {
+                    return Err(ParseError::new(format!(
+                        "expected {} captures, got {}",
+                        $n,
+                        captures.len()
+                    )));
+                }
+                Ok(($(parse_value::<$t>(captures[$i])?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs() {
+        let input = "1000\n2000\n\n4000\n\n\n5000\n6000\n";
+        let p = paragraphs(input);
+
+        assert_eq!(p.len(), 3);
+        assert_eq!(p[0].text, "1000\n2000");
+        assert_eq!((p[1].line, p[1].text), (4, "4000"));
+        assert_eq!(p[2].lines().collect::<Vec<_>>(), vec!["5000", "6000"]);
+
+        let err = p[2]
+            .parse_lines(|l| {
+                if l == "6000" {
+                    Err(ParseError::new("bad"))
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap_err();
+        assert_eq!(err.line, Some(8));
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints("x=-3, y=12 -> a-b 7"), vec![-3, 12, 7]);
+        assert_eq!(uints("2-4,6-8"), vec![2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_list_and_key_values() {
+        assert_eq!(list::<u32>("79, 98", ","), Ok(vec![79, 98]));
+        assert!(list::<u32>("79, x", ",").is_err());
+
+        let kv = key_values("Starting items: 79, 98\n  Test: divisible by 23", ":").unwrap();
+        assert_eq!(
+            kv,
+            vec![("Starting items", "79, 98"), ("Test", "divisible by 23")]
+        );
+
+        let err = key_values("a: 1\nb 2", ":").unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn test_template() {
+        let t = Template::new("move {} from {} to {}");
+
+        assert_eq!(
+            t.parse("move 13 from 2 to 9"),
+            Ok((13_u32, 2_usize, 9_usize))
+        );
+        assert!(t.parse::<(u32, u32, u32)>("move x from 2 to 9").is_err());
+        assert!(t.captures("move 1 from 2").is_err());
+
+        let err = t
+            .parse_lines::<(u32, u32, u32)>("move 1 from 2 to 1\nmove 3 from 1 to")
+            .unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.to_string().starts_with("line 2: "));
+
+        let t = Template::new("{}-{},{}-{}");
+        assert_eq!(t.parse("2-4,6-8"), Ok((2, 4, 6, 8)));
+    }
+}
@@ -0,0 +1,276 @@
+/*
+ * Generic graph searches over a successor function.
+ * Example import: `use advent_of_code::helpers::search::{bfs, dijkstra, astar};`.
+ */
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Integer cost used by the weighted searches.
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(impl Cost for $t {
+            const ZERO: Self = 0;
+        })*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// The first goal node reached by a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<N, C> {
+    pub goal: N,
+    pub cost: C,
+    /// Nodes from the start to `goal` (both included), if path tracking was requested.
+    pub path: Option<Vec<N>>,
+}
+
+impl<N, C> Found<N, C> {
+    /// The start node the goal was reached from, if the path was tracked.
+    pub fn start(&self) -> Option<&N> {
+        self.path.as_ref().and_then(|p| p.first())
+    }
+}
+
+// every discovered node lives in an arena; `parent` indexes into it.
+struct Arena<N, C> {
+    index: HashMap<N, usize>,
+    nodes: Vec<(N, C, Option<usize>)>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Arena<N, C> {
+    fn new() -> Self {
+        Arena {
+            index: HashMap::new(),
+            nodes: vec![],
+        }
+    }
+
+    fn path(&self, mut i: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[i].0.clone()];
+
+        while let Some(parent) = self.nodes[i].2 {
+            path.push(self.nodes[parent].0.clone());
+            i = parent;
+        }
+
+        path.reverse();
+        path
+    }
+
+    fn found(&self, i: usize, track_path: bool) -> Found<N, C> {
+        Found {
+            goal: self.nodes[i].0.clone(),
+            cost: self.nodes[i].1,
+            path: track_path.then(|| self.path(i)),
+        }
+    }
+}
+
+/// Breadth-first search where every edge costs 1.
+/// All `starts` are at distance 0; returns the nearest node matching `is_goal`.
+pub fn bfs<N, FN, IN, FG>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut is_goal: FG,
+    track_path: bool,
+) -> Option<Found<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut arena = Arena::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if let Entry::Vacant(e) = arena.index.entry(start.clone()) {
+            e.insert(arena.nodes.len());
+            queue.push_back(arena.nodes.len());
+            arena.nodes.push((start, 0, None));
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&arena.nodes[i].0) {
+            return Some(arena.found(i, track_path));
+        }
+
+        let dist = arena.nodes[i].1 + 1;
+
+        for next in successors(&arena.nodes[i].0) {
+            if let Entry::Vacant(e) = arena.index.entry(next.clone()) {
+                e.insert(arena.nodes.len());
+                queue.push_back(arena.nodes.len());
+                arena.nodes.push((next, dist, Some(i)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's shortest path with a binary heap. Successors yield `(node, edge_cost)`.
+pub fn dijkstra<N, C, FN, IN, FG>(
+    starts: impl IntoIterator<Item = N>,
+    successors: FN,
+    is_goal: FG,
+    track_path: bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::ZERO, is_goal, track_path)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a goal.
+pub fn astar<N, C, FN, IN, FH, FG>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+    track_path: bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    let mut arena: Arena<N, C> = Arena::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if let Entry::Vacant(e) = arena.index.entry(start.clone()) {
+            e.insert(arena.nodes.len());
+            heap.push(Reverse((heuristic(&start), C::ZERO, arena.nodes.len())));
+            arena.nodes.push((start, C::ZERO, None));
+        }
+    }
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        // stale heap entry, a cheaper route was found after it was pushed.
+        if cost > arena.nodes[i].1 {
+            continue;
+        }
+
+        if is_goal(&arena.nodes[i].0) {
+            return Some(arena.found(i, track_path));
+        }
+
+        for (next, step) in successors(&arena.nodes[i].0) {
+            let next_cost = cost + step;
+
+            let j = match arena.index.entry(next.clone()) {
+                Entry::Vacant(e) => {
+                    e.insert(arena.nodes.len());
+                    arena.nodes.push((next, next_cost, Some(i)));
+                    arena.nodes.len() - 1
+                }
+                Entry::Occupied(e) => {
+                    let j = *e.get();
+                    if next_cost >= arena.nodes[j].1 {
+                        continue;
+                    }
+                    arena.nodes[j].1 = next_cost;
+                    arena.nodes[j].2 = Some(i);
+                    j
+                }
+            };
+
+            heap.push(Reverse((
+                next_cost + heuristic(&arena.nodes[j].0),
+                next_cost,
+                j,
+            )));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 - 1 - 2
+    // |       |
+    // 3 - 4 - 5
+    fn ring(n: &u32) -> Vec<u32> {
+        match n {
+            0 => vec![1, 3],
+            1 => vec![0, 2],
+            2 => vec![1, 5],
+            3 => vec![0, 4],
+            4 => vec![3, 5],
+            5 => vec![2, 4],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let found = bfs([0], ring, |n| *n == 5, true).unwrap();
+        assert_eq!(found.cost, 3);
+        assert_eq!(found.path.unwrap().len(), 4);
+
+        assert_eq!(bfs([0], ring, |n| *n == 9, false), None);
+    }
+
+    #[test]
+    fn test_bfs_multi_source() {
+        let found = bfs([0, 4], ring, |n| *n == 5, true).unwrap();
+        assert_eq!(found.cost, 1);
+        assert_eq!(found.start(), Some(&4));
+        assert_eq!(found.path, Some(vec![4, 5]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // the short way round is expensive.
+        let weighted = |n: &u32| -> Vec<(u32, u32)> {
+            ring(n)
+                .into_iter()
+                .map(|m| (m, if *n == 1 || m == 1 { 10 } else { 1 }))
+                .collect()
+        };
+
+        let found = dijkstra([0], weighted, |n| *n == 2, true).unwrap();
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.path, Some(vec![0, 3, 4, 5, 2]));
+
+        let found = dijkstra([0], weighted, |n| *n == 2, false).unwrap();
+        assert_eq!(found.path, None);
+    }
+
+    #[test]
+    fn test_astar() {
+        // open 10x10 grid, manhattan heuristic.
+        let successors = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1))
+                .filter(|((x, y), _)| (0..10).contains(x) && (0..10).contains(y))
+        };
+        let heuristic = |&(x, y): &(i32, i32)| (9 - x) + (9 - y);
+
+        let found = astar([(0, 0)], successors, heuristic, |n| *n == (9, 9), true).unwrap();
+        assert_eq!(found.cost, 18);
+        assert_eq!(found.path.unwrap().len(), 19);
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Bounded collector for the k largest items of an iterator.
+ * Example import: `use advent_of_code::helpers::top_k::TopK;`.
+ */
+use std::{cmp::Reverse, collections::BinaryHeap, iter::Sum};
+
+/// Keeps the `k` largest items seen so far in a min-heap of size `k`.
+#[derive(Debug, Clone)]
+pub struct TopK<T: Ord> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    /// Collects the `k` largest items of `iter`.
+    pub fn of(k: usize, iter: impl IntoIterator<Item = T>) -> Self {
+        let mut top = TopK::new(k);
+        top.extend(iter);
+        top
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.k == 0 {
+            return;
+        }
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut min) = self.heap.peek_mut() {
+            if item > min.0 {
+                *min = Reverse(item);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The smallest item still in the top k, i.e. the bar a new item has to beat.
+    pub fn threshold(&self) -> Option<&T> {
+        self.heap.peek().map(|r| &r.0)
+    }
+
+    /// Largest first.
+    pub fn sorted(&self) -> Vec<&T> {
+        let mut items: Vec<&T> = self.heap.iter().map(|r| &r.0).collect();
+        items.sort_by(|a, b| b.cmp(a));
+        items
+    }
+
+    /// Largest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // ascending order of `Reverse<T>` is descending order of `T`.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|r| r.0)
+            .collect()
+    }
+
+    /// Items with their 1-based rank; equal items share a rank (`1, 2, 2, 4`).
+    pub fn ranked(&self) -> Vec<(usize, &T)> {
+        let sorted = self.sorted();
+        let mut out: Vec<(usize, &T)> = Vec::with_capacity(sorted.len());
+
+        for (i, item) in sorted.into_iter().enumerate() {
+            let rank = match out.last() {
+                Some((rank, prev)) if *prev == item => *rank,
+                _ => i + 1,
+            };
+            out.push((rank, item));
+        }
+
+        out
+    }
+
+    pub fn sum<S>(&self) -> S
+    where
+        S: for<'a> Sum<&'a T>,
+    {
+        self.heap.iter().map(|r| &r.0).sum()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let top = TopK::of(3, [6000_u32, 4000, 11000, 24000, 10000]);
+
+        assert_eq!(top.sorted(), vec![&24000, &11000, &10000]);
+        assert_eq!(top.sum::<u32>(), 45000);
+        assert_eq!(top.threshold(), Some(&10000));
+        assert_eq!(top.into_sorted_vec(), vec![24000, 11000, 10000]);
+    }
+
+    #[test]
+    fn test_top_k_edge_cases() {
+        let top = TopK::of(5, [1, 2]);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top.sum::<i32>(), 3);
+
+        let top = TopK::of(0, [1, 2]);
+        assert!(top.is_empty());
+    }
+
+    #[test]
+    fn test_ranked() {
+        let top = TopK::of(4, [5, 9, 7, 7, 1]);
+        assert_eq!(top.ranked(), vec![(1, &9), (2, &7), (2, &7), (4, &5)]);
+    }
+}
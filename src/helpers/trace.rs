@@ -0,0 +1,137 @@
+/*
+ * Leveled debug output for solutions, written to stderr.
+ * Example: `advent_of_code::debug!("move {} from {} to {}", n, from, to);`.
+ *
+ * Output is enabled with `-v` (debug) / `-vv` (trace) after `--`, e.g. `cargo solve 05 -- -v`,
+ * or with `AOC_VERBOSE=debug|trace`. Release builds (`cargo all`) compile it out entirely
+ * unless the `trace` feature is enabled.
+ */
+#[cfg(any(debug_assertions, feature = "trace"))]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+#[cfg(any(debug_assertions, feature = "trace"))]
+const UNSET: u8 = u8::MAX;
+
+#[cfg(any(debug_assertions, feature = "trace"))]
+static VERBOSITY: AtomicU8 = AtomicU8::new(UNSET);
+
+#[cfg(any(debug_assertions, feature = "trace"))]
+fn verbosity_from_env() -> u8 {
+    let from_args = std::env::args()
+        .skip(1)
+        .map(|arg| verbosity_flag(&arg).unwrap_or(0))
+        .fold(0_u8, |acc, v| acc.saturating_add(v));
+
+    let from_env = match std::env::var("AOC_VERBOSE").as_deref() {
+        Ok("debug") | Ok("1") => 1,
+        Ok("trace") | Ok("2") => 2,
+        _ => 0,
+    };
+
+    from_args.max(from_env)
+}
+
+/// The verbosity a command line argument asks for: `-v`, `-vv`, ... or `--verbose`.
+pub fn verbosity_flag(arg: &str) -> Option<u8> {
+    match arg {
+        "--verbose" => Some(1),
+        arg if arg.len() > 1 && arg.starts_with('-') && arg[1..].bytes().all(|b| b == b'v') => {
+            Some((arg.len() - 1).min(u8::MAX as usize) as u8)
+        }
+        _ => None,
+    }
+}
+
+/// Whether messages at `level` are printed. Constant `false` in release builds.
+#[cfg(any(debug_assertions, feature = "trace"))]
+pub fn enabled(level: Level) -> bool {
+    let mut verbosity = VERBOSITY.load(Ordering::Relaxed);
+
+    if verbosity == UNSET {
+        verbosity = verbosity_from_env();
+        VERBOSITY.store(verbosity, Ordering::Relaxed);
+    }
+
+    verbosity >= level as u8
+}
+
+#[cfg(not(any(debug_assertions, feature = "trace")))]
+#[inline(always)]
+pub const fn enabled(_level: Level) -> bool {
+    false
+}
+
+/// Overrides the verbosity picked up from the command line and environment.
+/// `None` silences all output.
+pub fn set_level(level: Option<Level>) {
+    #[cfg(any(debug_assertions, feature = "trace"))]
+    VERBOSITY.store(level.map_or(0, |l| l as u8), Ordering::Relaxed);
+
+    #[cfg(not(any(debug_assertions, feature = "trace")))]
+    let _ = level;
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {{
+        if $crate::helpers::trace::enabled($level) {
+            eprintln!($($arg)*);
+        }
+    }};
+}
+
+/// Per-step output, e.g. every instruction executed.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::log!($crate::helpers::trace::Level::Debug, $($arg)*)
+    };
+}
+
+/// Very noisy output, e.g. full state dumps after every step.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::log!($crate::helpers::trace::Level::Trace, $($arg)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        set_level(Some(Level::Debug));
+        assert!(enabled(Level::Debug));
+        assert!(!enabled(Level::Trace));
+
+        set_level(Some(Level::Trace));
+        assert!(enabled(Level::Trace));
+
+        set_level(None);
+        assert!(!enabled(Level::Debug));
+
+        // must expand and type-check even when disabled.
+        crate::debug!("{} {:?}", 1, vec![2]);
+        crate::trace!("{}", 3);
+    }
+
+    #[test]
+    fn test_verbosity_flag() {
+        assert_eq!(verbosity_flag("-v"), Some(1));
+        assert_eq!(verbosity_flag("-vv"), Some(2));
+        assert_eq!(verbosity_flag("--verbose"), Some(1));
+        assert_eq!(verbosity_flag(&format!("-{}", "v".repeat(300))), Some(u8::MAX));
+
+        assert_eq!(verbosity_flag("-"), None);
+        assert_eq!(verbosity_flag("-vx"), None);
+        assert_eq!(verbosity_flag("--vv"), None);
+    }
+}
@@ -0,0 +1,365 @@
+/*
+ * Arena-backed virtual filesystem for shell-transcript puzzles.
+ * Example import: `use advent_of_code::helpers::vfs::FileSystem;`.
+ */
+use crate::helpers::parse::ParseError;
+use std::{cell::OnceCell, collections::BTreeMap, error::Error, fmt};
+
+pub type NodeId = usize;
+
+pub const ROOT: NodeId = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Dir(BTreeMap<String, NodeId>),
+    File(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsError {
+    NotFound(String),
+    NotADirectory(String),
+    IsADirectory(String),
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsError::NotFound(p) => write!(f, "{}: no such file or directory", p),
+            FsError::NotADirectory(p) => write!(f, "{}: not a directory", p),
+            FsError::IsADirectory(p) => write!(f, "{}: is a directory", p),
+        }
+    }
+}
+
+impl Error for FsError {}
+
+impl From<FsError> for ParseError {
+    fn from(e: FsError) -> Self {
+        ParseError::new(e.to_string())
+    }
+}
+
+/// All nodes live in one `Vec`; ids are indices and `ROOT` is `/`.
+#[derive(Debug, Clone)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+    cwd: NodeId,
+    // recursive sizes, computed on first query and dropped on every change.
+    sizes: OnceCell<Vec<u64>>,
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        FileSystem {
+            nodes: vec![Node {
+                name: String::from("/"),
+                parent: None,
+                kind: Kind::Dir(BTreeMap::new()),
+            }],
+            cwd: ROOT,
+            sizes: OnceCell::new(),
+        }
+    }
+
+    /// Replays `$ cd <path>` / `$ ls` transcripts and their `dir <name>` / `<size> <name>` output.
+    pub fn from_transcript(input: &str) -> Result<Self, ParseError> {
+        let mut fs = FileSystem::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            let result = match parts.as_slice() {
+                [] => Ok(()),
+                ["$", "cd", path] => fs.cd(path).map_err(ParseError::from),
+                ["$", "ls"] => Ok(()),
+                ["dir", name] => fs.mkdir(name).map(|_| ()).map_err(ParseError::from),
+                [size, name] => match size.parse() {
+                    Ok(size) => fs
+                        .add_file(name, size)
+                        .map(|_| ())
+                        .map_err(ParseError::from),
+                    Err(_) => Err(ParseError::new(format!("unexpected output {:?}", line))),
+                },
+                _ => Err(ParseError::new(format!("unknown command {:?}", line))),
+            };
+
+            result.map_err(|e| e.at_line(i + 1))?;
+        }
+
+        Ok(fs)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always false, the root directory always exists.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn cwd(&self) -> NodeId {
+        self.cwd
+    }
+
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[id].kind {
+            Kind::Dir(children) => Some(children.values().copied()),
+            Kind::File(_) => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    /// Absolute path of a node, e.g. `/a/e`.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut cur = id;
+
+        while let Some(parent) = self.nodes[cur].parent {
+            names.push(self.nodes[cur].name.as_str());
+            cur = parent;
+        }
+
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Resolves an absolute or relative path (`..` and `.` allowed) from the current directory.
+    pub fn resolve(&self, path: &str) -> Result<NodeId, FsError> {
+        let mut cur = if path.starts_with('/') {
+            ROOT
+        } else {
+            self.cwd
+        };
+
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            cur = match (part, &self.nodes[cur].kind) {
+                (_, Kind::File(_)) => return Err(FsError::NotADirectory(self.path(cur))),
+                (".", _) => cur,
+                ("..", _) => self.nodes[cur].parent.unwrap_or(ROOT),
+                (name, Kind::Dir(children)) => *children
+                    .get(name)
+                    .ok_or_else(|| FsError::NotFound(String::from(path)))?,
+            };
+        }
+
+        Ok(cur)
+    }
+
+    pub fn cd(&mut self, path: &str) -> Result<(), FsError> {
+        let target = self.resolve(path)?;
+
+        if !self.nodes[target].is_dir() {
+            return Err(FsError::NotADirectory(self.path(target)));
+        }
+
+        self.cwd = target;
+        Ok(())
+    }
+
+    /// Creates a directory in the current directory, or returns the existing one.
+    pub fn mkdir(&mut self, name: &str) -> Result<NodeId, FsError> {
+        match self.child(name) {
+            Some(id) if self.nodes[id].is_dir() => Ok(id),
+            Some(id) => Err(FsError::NotADirectory(self.path(id))),
+            None => Ok(self.insert(name, Kind::Dir(BTreeMap::new()))),
+        }
+    }
+
+    /// Creates a file in the current directory. Listing the same file again only
+    /// updates its size, so repeated `ls` output is not counted twice.
+    pub fn add_file(&mut self, name: &str, size: u64) -> Result<NodeId, FsError> {
+        match self.child(name) {
+            Some(id) if self.nodes[id].is_dir() => Err(FsError::IsADirectory(self.path(id))),
+            Some(id) => {
+                self.nodes[id].kind = Kind::File(size);
+                self.sizes.take();
+                Ok(id)
+            }
+            None => Ok(self.insert(name, Kind::File(size))),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<NodeId> {
+        match &self.nodes[self.cwd].kind {
+            Kind::Dir(children) => children.get(name).copied(),
+            Kind::File(_) => None,
+        }
+    }
+
+    fn insert(&mut self, name: &str, kind: Kind) -> NodeId {
+        let id = self.nodes.len();
+
+        self.nodes.push(Node {
+            name: String::from(name),
+            parent: Some(self.cwd),
+            kind,
+        });
+
+        if let Kind::Dir(children) = &mut self.nodes[self.cwd].kind {
+            children.insert(String::from(name), id);
+        }
+
+        self.sizes.take();
+        id
+    }
+
+    /// Recursive size: a file's own size, or the sum of everything below a directory.
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.sizes.get_or_init(|| self.compute_sizes())[id]
+    }
+
+    fn compute_sizes(&self) -> Vec<u64> {
+        let mut sizes = vec![0; self.nodes.len()];
+
+        // children are always created after their parent, so walking the arena
+        // backwards visits every child before its parent.
+        for id in (0..self.nodes.len()).rev() {
+            if let Kind::File(size) = self.nodes[id].kind {
+                sizes[id] = size;
+            }
+            if let Some(parent) = self.nodes[id].parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+
+        sizes
+    }
+
+    /// Depth-first walk from `id` yielding `(depth, node)`; children in name order.
+    pub fn walk(&self, id: NodeId) -> Walk<'_> {
+        Walk {
+            fs: self,
+            stack: vec![(0, id)],
+        }
+    }
+
+    /// Every directory, including the root.
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.walk(ROOT)
+            .map(|(_, id)| id)
+            .filter(|id| self.nodes[*id].is_dir())
+    }
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Walk<'a> {
+    fs: &'a FileSystem,
+    stack: Vec<(usize, NodeId)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (usize, NodeId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, id) = self.stack.pop()?;
+
+        let mut children: Vec<NodeId> = self.fs.children(id).collect();
+        children.reverse();
+        self.stack
+            .extend(children.into_iter().map(|child| (depth + 1, child)));
+
+        Some((depth, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn test_sizes() {
+        let fs = FileSystem::from_transcript(TRANSCRIPT).unwrap();
+
+        assert_eq!(fs.size(fs.resolve("/a/e").unwrap()), 584);
+        assert_eq!(fs.size(fs.resolve("/a").unwrap()), 94853);
+        assert_eq!(fs.size(fs.resolve("/d").unwrap()), 24933642);
+        assert_eq!(fs.size(ROOT), 48381165);
+    }
+
+    #[test]
+    fn test_repeated_ls_is_idempotent() {
+        let twice = format!("{}\n$ ls\n4060174 j\n8033020 d.log", TRANSCRIPT);
+        let fs = FileSystem::from_transcript(&twice).unwrap();
+        assert_eq!(fs.size(ROOT), 48381165);
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut fs = FileSystem::from_transcript(TRANSCRIPT).unwrap();
+
+        fs.cd("/a").unwrap();
+        assert_eq!(fs.path(fs.resolve("e/i").unwrap()), "/a/e/i");
+        assert_eq!(fs.path(fs.resolve("../d/./k").unwrap()), "/d/k");
+        assert_eq!(fs.resolve("x"), Err(FsError::NotFound(String::from("x"))));
+        assert!(matches!(fs.cd("f"), Err(FsError::NotADirectory(_))));
+        assert!(matches!(fs.add_file("e", 1), Err(FsError::IsADirectory(_))));
+    }
+
+    #[test]
+    fn test_walk() {
+        let fs = FileSystem::from_transcript(TRANSCRIPT).unwrap();
+
+        let names: Vec<(usize, &str)> = fs
+            .walk(ROOT)
+            .take(4)
+            .map(|(depth, id)| (depth, fs.node(id).name.as_str()))
+            .collect();
+        assert_eq!(names, vec![(0, "/"), (1, "a"), (2, "e"), (3, "i")]);
+
+        assert_eq!(fs.dirs().count(), 4);
+    }
+
+    #[test]
+    fn test_transcript_errors() {
+        let err = FileSystem::from_transcript("$ cd /\n$ cd nowhere").unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+}
@@ -0,0 +1,539 @@
+/*
+ * Rendering puzzle state to the terminal and to image files.
+ * Example import: `use advent_of_code::helpers::viz::{Render, Cell, Terminal};`.
+ */
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub type Rgb = (u8, u8, u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    /// Foreground color in the terminal, fill color in images.
+    pub color: Option<Rgb>,
+}
+
+impl Cell {
+    pub fn new(ch: char) -> Self {
+        Cell { ch, color: None }
+    }
+
+    pub fn colored(ch: char, color: Rgb) -> Self {
+        Cell {
+            ch,
+            color: Some(color),
+        }
+    }
+
+    /// The explicit color, or a fixed one derived from the symbol.
+    pub fn fill(&self) -> Rgb {
+        self.color.unwrap_or(match self.ch {
+            ' ' | '.' => (0, 0, 0),
+            '#' => (255, 255, 255),
+            ch => {
+                // stable pseudo-random color per symbol.
+                let h = (ch as u32).wrapping_mul(2654435761);
+                (
+                    96 + (h >> 24) as u8 % 160,
+                    96 + (h >> 16) as u8 % 160,
+                    96 + (h >> 8) as u8 % 160,
+                )
+            }
+        })
+    }
+}
+
+/// Anything that can be drawn as a `width x height` grid of cells, row 0 at the top.
+pub trait Render {
+    fn size(&self) -> (usize, usize);
+    fn cell(&self, x: usize, y: usize) -> Cell;
+}
+
+impl Render for Vec<Vec<char>> {
+    fn size(&self) -> (usize, usize) {
+        let width = self.iter().map(Vec::len).max().unwrap_or(0);
+        (width, self.len())
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Cell {
+        Cell::new(self[y].get(x).copied().unwrap_or(' '))
+    }
+}
+
+impl Render for Vec<Vec<Cell>> {
+    fn size(&self) -> (usize, usize) {
+        let width = self.iter().map(Vec::len).max().unwrap_or(0);
+        (width, self.len())
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Cell {
+        self[y].get(x).copied().unwrap_or(Cell::new(' '))
+    }
+}
+
+/// Points on an unbounded integer plane; the view covers every point (or fixed bounds).
+#[derive(Debug, Clone)]
+pub struct Sparse {
+    points: HashMap<(i64, i64), Cell>,
+    background: Cell,
+    bounds: Option<((i64, i64), (i64, i64))>,
+    /// Smallest box holding every point, grown on insert so rendering doesn't rescan.
+    extent: Option<((i64, i64), (i64, i64))>,
+    y_up: bool,
+}
+
+impl Sparse {
+    pub fn new(background: char) -> Self {
+        Sparse {
+            points: HashMap::new(),
+            background: Cell::new(background),
+            bounds: None,
+            extent: None,
+            y_up: false,
+        }
+    }
+
+    /// Draw larger `y` higher up, like the puzzle's coordinate drawings.
+    pub fn y_up(mut self) -> Self {
+        self.y_up = true;
+        self
+    }
+
+    /// Fix the view to `min..=max` instead of fitting it to the points.
+    pub fn with_bounds(mut self, min: (i64, i64), max: (i64, i64)) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Points drawn later win over earlier ones at the same position.
+    pub fn insert(&mut self, pos: (i64, i64), cell: Cell) {
+        self.grow(pos);
+        self.points.insert(pos, cell);
+    }
+
+    /// Only sets `pos` if nothing was drawn there yet.
+    pub fn insert_under(&mut self, pos: (i64, i64), cell: Cell) {
+        self.grow(pos);
+        self.points.entry(pos).or_insert(cell);
+    }
+
+    fn grow(&mut self, (x, y): (i64, i64)) {
+        self.extent = Some(match self.extent {
+            Some(((x0, y0), (x1, y1))) => ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y))),
+            None => ((x, y), (x, y)),
+        });
+    }
+
+    fn view(&self) -> ((i64, i64), (i64, i64)) {
+        self.bounds.or(self.extent).unwrap_or(((0, 0), (-1, -1)))
+    }
+}
+
+impl Render for Sparse {
+    fn size(&self) -> (usize, usize) {
+        let ((x0, y0), (x1, y1)) = self.view();
+        ((x1 - x0 + 1).max(0) as usize, (y1 - y0 + 1).max(0) as usize)
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Cell {
+        let ((x0, y0), (_, y1)) = self.view();
+        let px = x0 + x as i64;
+        let py = if self.y_up {
+            y1 - y as i64
+        } else {
+            y0 + y as i64
+        };
+
+        self.points
+            .get(&(px, py))
+            .copied()
+            .unwrap_or(self.background)
+    }
+}
+
+/// Linear blue -> green -> red scale for heatmaps; `t` is clamped to `0.0..=1.0`.
+pub fn heat(t: f32) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    (
+        channel(2.0 * t - 1.0),
+        channel(1.0 - (2.0 * t - 1.0).abs()),
+        channel(1.0 - 2.0 * t),
+    )
+}
+
+/// Plain text, one line per row.
+pub fn to_text(r: &dyn Render) -> String {
+    let (width, height) = r.size();
+    let mut out = String::with_capacity((width + 1) * height);
+
+    for y in 0..height {
+        for x in 0..width {
+            out.push(r.cell(x, y).ch);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Text with 24-bit ANSI colors for cells that have one.
+pub fn to_ansi(r: &dyn Render) -> String {
+    let (width, height) = r.size();
+    let mut out = String::new();
+
+    for y in 0..height {
+        let mut current = None;
+
+        for x in 0..width {
+            let cell = r.cell(x, y);
+
+            if cell.color != current {
+                match cell.color {
+                    Some((red, green, blue)) => {
+                        let _ = write!(out, "\x1b[38;2;{};{};{}m", red, green, blue);
+                    }
+                    None => out.push_str(crate::ANSI_RESET),
+                }
+                current = cell.color;
+            }
+
+            out.push(cell.ch);
+        }
+
+        if current.is_some() {
+            out.push_str(crate::ANSI_RESET);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Redraws frames in place at a fixed maximum frame rate.
+pub struct Terminal<W: Write> {
+    out: W,
+    frame_time: Option<Duration>,
+    last_frame: Option<Instant>,
+    frames: usize,
+}
+
+impl Terminal<io::Stdout> {
+    pub fn stdout(fps: u32) -> Self {
+        Terminal::new(io::stdout(), fps)
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    /// `fps == 0` draws as fast as possible.
+    pub fn new(out: W, fps: u32) -> Self {
+        Terminal {
+            out,
+            frame_time: (fps > 0).then(|| Duration::from_secs_f64(1.0 / fps as f64)),
+            last_frame: None,
+            frames: 0,
+        }
+    }
+
+    /// Clears the screen before the first frame, then moves the cursor home and
+    /// overwrites the previous frame.
+    pub fn draw(&mut self, r: &dyn Render) -> io::Result<()> {
+        self.draw_with_caption(r, "")
+    }
+
+    pub fn draw_with_caption(&mut self, r: &dyn Render, caption: &str) -> io::Result<()> {
+        if let (Some(frame_time), Some(last)) = (self.frame_time, self.last_frame) {
+            if let Some(wait) = frame_time.checked_sub(last.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+
+        let clear = if self.frames == 0 { "\x1b[2J" } else { "" };
+        // `\x1b[J` drops leftovers when a frame is smaller than the last one.
+        write!(self.out, "{}\x1b[H{}\x1b[J{}", clear, to_ansi(r), caption)?;
+        if !caption.is_empty() {
+            writeln!(self.out)?;
+        }
+        self.out.flush()?;
+
+        self.last_frame = Some(Instant::now());
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Records frames as an asciinema v2 recording (`.cast`), replayable with `asciinema play`.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    frame_time: f64,
+    width: usize,
+    height: usize,
+    frames: Vec<String>,
+}
+
+impl Cast {
+    pub fn new(fps: u32) -> Self {
+        Cast {
+            frame_time: 1.0 / fps.max(1) as f64,
+            width: 0,
+            height: 0,
+            frames: vec![],
+        }
+    }
+
+    pub fn push(&mut self, r: &dyn Render, caption: &str) {
+        let (width, height) = r.size();
+        self.width = self.width.max(width).max(caption.chars().count());
+        self.height = self.height.max(height + 1);
+
+        let frame = format!("\x1b[H\x1b[J{}{}", to_ansi(r), caption).replace('\n', "\r\n");
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            r#"{{"version": 2, "width": {}, "height": {}}}"#,
+            self.width.max(1),
+            self.height.max(1)
+        )?;
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            writeln!(
+                out,
+                r#"[{:.3}, "o", "{}"]"#,
+                i as f64 * self.frame_time,
+                json_escape(frame)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Binary PPM (P6); every cell becomes a `scale x scale` block of its fill color.
+pub fn write_ppm(r: &dyn Render, scale: usize, out: &mut impl Write) -> io::Result<()> {
+    let (width, height) = r.size();
+    let scale = scale.max(1);
+
+    write!(out, "P6\n{} {}\n255\n", width * scale, height * scale)?;
+
+    let mut row = Vec::with_capacity(width * scale * 3);
+    for y in 0..height {
+        row.clear();
+        for x in 0..width {
+            let (red, green, blue) = r.cell(x, y).fill();
+            for _ in 0..scale {
+                row.extend_from_slice(&[red, green, blue]);
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&row)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// SVG with one filled square per non-background cell, labelled with its symbol.
+pub fn to_svg(r: &dyn Render, cell_size: usize) -> String {
+    let (width, height) = r.size();
+    let s = cell_size.max(1);
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width * s,
+        height * s,
+        width * s,
+        height * s
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="100%" height="100%" fill="black"/><g font-family="monospace" font-size="{}" text-anchor="middle">"#,
+        s * 3 / 4
+    );
+
+    for y in 0..height {
+        for x in 0..width {
+            let cell = r.cell(x, y);
+            if cell.ch == ' ' || (cell.ch == '.' && cell.color.is_none()) {
+                continue;
+            }
+
+            let (red, green, blue) = cell.fill();
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="rgb({},{},{})"/>"#,
+                x * s,
+                y * s,
+                red,
+                green,
+                blue,
+            );
+
+            if cell.ch != '#' {
+                let label = match cell.ch {
+                    '<' => String::from("&lt;"),
+                    '>' => String::from("&gt;"),
+                    '&' => String::from("&amp;"),
+                    ch => ch.to_string(),
+                };
+                // dark text on light cells and vice versa.
+                let luma = red as u32 * 3 + green as u32 * 6 + blue as u32;
+                let text = if luma > 1280 { "black" } else { "white" };
+                let _ = writeln!(
+                    out,
+                    r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                    x * s + s / 2,
+                    y * s + s * 3 / 4,
+                    text,
+                    label
+                );
+            }
+        }
+    }
+
+    out.push_str("</g></svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str) -> Vec<Vec<char>> {
+        s.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_text() {
+        let g = grid("#.#\n.#");
+        assert_eq!(g.size(), (3, 2));
+        assert_eq!(to_text(&g), "#.#\n.# \n");
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut s = Sparse::new('.').y_up();
+        s.insert((0, 0), Cell::new('s'));
+        s.insert((2, 1), Cell::new('H'));
+        s.insert_under((2, 1), Cell::new('T'));
+
+        assert_eq!(to_text(&s), "..H\ns..\n");
+
+        let s = s.with_bounds((-1, 0), (2, 0));
+        assert_eq!(to_text(&s), ".s..\n");
+
+        assert_eq!(Sparse::new('.').size(), (0, 0));
+
+        let mut s = Sparse::new('.');
+        s.insert((-2, 3), Cell::new('a'));
+        s.insert_under((1, 4), Cell::new('b'));
+        assert_eq!(to_text(&s), "a...\n...b\n");
+    }
+
+    #[test]
+    fn test_ansi_and_terminal() {
+        let g = vec![vec![Cell::colored('a', (255, 0, 0)), Cell::new('b')]];
+        assert!(to_ansi(&g).starts_with("\x1b[38;2;255;0;0ma"));
+
+        let mut term = Terminal::new(vec![], 0);
+        term.draw(&g).unwrap();
+        term.draw_with_caption(&g, "step 2").unwrap();
+        assert_eq!(term.frames(), 2);
+
+        let out = String::from_utf8(term.into_inner()).unwrap();
+        assert!(out.starts_with("\x1b[2J\x1b[H"));
+        assert!(out.ends_with("step 2\n"));
+    }
+
+    #[test]
+    fn test_cast() {
+        let mut cast = Cast::new(10);
+        cast.push(&grid("ab\ncd"), "step \"1\"");
+        cast.push(&grid("ef"), "");
+
+        let mut out = vec![];
+        cast.write(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], r#"{"version": 2, "width": 8, "height": 3}"#);
+        assert_eq!(
+            lines[1],
+            r#"[0.000, "o", "\u001b[H\u001b[Jab\r\ncd\r\nstep \"1\""]"#
+        );
+        assert!(lines[2].starts_with("[0.100, "));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let g = grid("#.");
+        let mut out = vec![];
+        write_ppm(&g, 2, &mut out).unwrap();
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 4 * 2 * 3);
+        assert_eq!(&out[header.len()..header.len() + 3], &[255, 255, 255]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = to_svg(&grid("#>."), 10);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains("&gt;"));
+    }
+
+    #[test]
+    fn test_heat() {
+        assert_eq!(heat(0.0), (0, 0, 255));
+        assert_eq!(heat(0.5), (0, 255, 0));
+        assert_eq!(heat(1.0), (255, 0, 0));
+    }
+}
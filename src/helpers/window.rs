@@ -0,0 +1,141 @@
+/*
+ * Sliding-window detection of runs of pairwise distinct symbols.
+ * Example import: `use advent_of_code::helpers::window::first_distinct;`.
+ */
+use std::io::{self, BufRead};
+
+/// Tracks the last `n` bytes with per-symbol counts, so each push is O(1).
+#[derive(Debug, Clone)]
+pub struct DistinctWindow {
+    ring: Vec<u8>,
+    counts: [u32; 256],
+    // number of symbols that occur more than once in the window.
+    repeated: usize,
+    consumed: usize,
+}
+
+impl DistinctWindow {
+    pub fn new(n: usize) -> Self {
+        DistinctWindow {
+            ring: vec![0; n],
+            counts: [0; 256],
+            repeated: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Adds a byte; returns whether the last `n` bytes are now pairwise distinct.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let n = self.ring.len();
+        if n == 0 {
+            self.consumed += 1;
+            return true;
+        }
+
+        let slot = self.consumed % n;
+
+        if self.consumed >= n {
+            let old = self.ring[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.repeated -= 1;
+            }
+        }
+
+        self.ring[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
+
+        self.consumed += 1;
+        self.is_distinct()
+    }
+
+    /// The window is full and holds no symbol twice.
+    pub fn is_distinct(&self) -> bool {
+        self.consumed >= self.ring.len() && self.repeated == 0
+    }
+
+    /// Number of bytes pushed so far.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+}
+
+/// Number of bytes read when the last `n` first become pairwise distinct,
+/// e.g. `first_distinct(b"mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4) == Some(7)`.
+pub fn first_distinct(bytes: &[u8], n: usize) -> Option<usize> {
+    all_distinct(bytes, n).next()
+}
+
+/// Every position (bytes read so far) at which the last `n` bytes are pairwise distinct.
+pub fn all_distinct(bytes: &[u8], n: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut window = DistinctWindow::new(n);
+
+    bytes
+        .iter()
+        .enumerate()
+        .filter(move |(_, b)| window.push(**b))
+        .map(|(i, _)| i + 1)
+}
+
+/// Like `first_distinct`, reading from a stream without loading it into memory.
+/// Line breaks are consumed like any other byte.
+pub fn first_distinct_in<R: BufRead>(mut reader: R, n: usize) -> io::Result<Option<usize>> {
+    let mut window = DistinctWindow::new(n);
+
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(None);
+        }
+
+        let len = buf.len();
+        for (i, b) in buf.iter().enumerate() {
+            if window.push(*b) {
+                reader.consume(i + 1);
+                return Ok(Some(window.consumed()));
+            }
+        }
+        reader.consume(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_first_distinct() {
+        let cases = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+
+        for (input, four, fourteen) in cases {
+            assert_eq!(first_distinct(input.as_bytes(), 4), Some(four));
+            assert_eq!(first_distinct(input.as_bytes(), 14), Some(fourteen));
+        }
+
+        assert_eq!(first_distinct(b"aaaa", 2), None);
+        assert_eq!(first_distinct(b"abc", 4), None);
+    }
+
+    #[test]
+    fn test_all_distinct() {
+        let positions: Vec<usize> = all_distinct(b"abcabbab", 3).collect();
+        assert_eq!(positions, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_first_distinct_in() {
+        // tiny buffer so the window spans several reads.
+        let reader = BufReader::with_capacity(3, "mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes());
+        assert_eq!(first_distinct_in(reader, 14).unwrap(), Some(19));
+    }
+}